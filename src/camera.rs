@@ -2,12 +2,14 @@
 
 use crate::{
     get_context,
+    input::{is_key_down, is_mouse_button_down, mouse_wheel, KeyCode, MouseButton},
     math::Rect,
     prelude::RenderPass,
     texture::RenderTarget,
+    time::get_frame_time,
     window::{screen_height, screen_width},
 };
-use glam::{vec2, vec3, Mat4, Vec2, Vec3};
+use glam::{vec2, vec3, Mat4, Quat, Vec2, Vec3};
 
 pub trait Camera {
     fn matrix(&self) -> Mat4;
@@ -215,6 +217,158 @@ impl Default for Camera3D {
     }
 }
 
+impl Camera3D {
+    /// Perspective camera at `position` looking at `target`, other fields are defaults.
+    pub fn look_at(position: Vec3, target: Vec3, up: Vec3) -> Camera3D {
+        Camera3D {
+            position,
+            target,
+            up,
+            ..Default::default()
+        }
+    }
+}
+
+/// Keeps the angle between `direction` and `up` away from 0 and PI, where looking straight
+/// up or down would flip the view.
+const MIN_PITCH_ANGLE: f32 = 0.01;
+
+/// Rotates `direction` by `yaw` radians around `up` and tilts it by `pitch` radians towards `up`.
+fn turn(direction: Vec3, up: Vec3, yaw: f32, pitch: f32) -> Vec3 {
+    let direction = Quat::from_axis_angle(up, yaw) * direction;
+    let right = direction.cross(up).normalize_or_zero();
+    if right == Vec3::ZERO {
+        return direction;
+    }
+
+    let angle = direction.angle_between(up);
+    let new_angle = (angle - pitch).clamp(MIN_PITCH_ANGLE, std::f32::consts::PI - MIN_PITCH_ANGLE);
+    Quat::from_axis_angle(right, angle - new_angle) * direction
+}
+
+/// First person controls for a [Camera3D]: mouse to look around, WASD to move,
+/// Space and LeftShift to move along the camera up axis.
+///
+/// Call [FpsController::update] once per frame before `set_camera`.
+#[derive(Debug, Clone)]
+pub struct FpsController {
+    /// Units per second.
+    pub move_speed: f32,
+    /// Radians per logical pixel of mouse movement.
+    pub mouse_sensitivity: f32,
+}
+
+impl Default for FpsController {
+    fn default() -> FpsController {
+        FpsController {
+            move_speed: 5.,
+            mouse_sensitivity: 0.003,
+        }
+    }
+}
+
+impl FpsController {
+    pub fn new() -> FpsController {
+        FpsController::default()
+    }
+
+    /// Grab and hide the cursor for mouse look, or release it.
+    ///
+    /// Grabbing keeps the cursor inside the window, it is best released when the window loses focus.
+    pub fn grab_cursor(&mut self, grab: bool) {
+        crate::input::set_cursor_grab(grab);
+        crate::input::show_mouse(!grab);
+    }
+
+    /// Apply this frame's mouse movement and pressed keys to `camera`.
+    pub fn update(&mut self, camera: &mut Camera3D) {
+        let up = camera.up.normalize_or_zero();
+        let direction = (camera.target - camera.position).normalize_or_zero();
+        let delta = crate::input::mouse_delta();
+        if up == Vec3::ZERO || direction == Vec3::ZERO {
+            return;
+        }
+
+        let direction = turn(direction, up, -delta.x * self.mouse_sensitivity, -delta.y * self.mouse_sensitivity);
+        let forward = (direction - up * direction.dot(up)).normalize_or_zero();
+        let right = forward.cross(up);
+
+        let mut movement = Vec3::ZERO;
+        for (key, axis) in [
+            (KeyCode::W, forward),
+            (KeyCode::S, -forward),
+            (KeyCode::D, right),
+            (KeyCode::A, -right),
+            (KeyCode::Space, up),
+            (KeyCode::LeftShift, -up),
+        ] {
+            if is_key_down(key) {
+                movement += axis;
+            }
+        }
+
+        camera.position += movement.normalize_or_zero() * self.move_speed * get_frame_time();
+        camera.target = camera.position + direction;
+    }
+}
+
+/// Orbit controls for a [Camera3D]: drag to rotate around the camera target, mouse wheel to zoom.
+///
+/// Call [OrbitController::update] once per frame before `set_camera`.
+#[derive(Debug, Clone)]
+pub struct OrbitController {
+    /// Mouse button rotating the camera while held.
+    pub button: MouseButton,
+    /// Radians per logical pixel of mouse movement.
+    pub mouse_sensitivity: f32,
+    /// Fraction of the distance to the target zoomed per mouse wheel step.
+    pub zoom_speed: f32,
+    pub min_distance: f32,
+    pub max_distance: f32,
+}
+
+impl Default for OrbitController {
+    fn default() -> OrbitController {
+        OrbitController {
+            button: MouseButton::Left,
+            mouse_sensitivity: 0.01,
+            zoom_speed: 0.1,
+            min_distance: 0.1,
+            max_distance: 1000.,
+        }
+    }
+}
+
+impl OrbitController {
+    pub fn new() -> OrbitController {
+        OrbitController::default()
+    }
+
+    /// Apply this frame's mouse drag and wheel to `camera`, keeping `camera.target` in place.
+    pub fn update(&mut self, camera: &mut Camera3D) {
+        let up = camera.up.normalize_or_zero();
+        let offset = camera.position - camera.target;
+        let delta = crate::input::mouse_delta();
+        if up == Vec3::ZERO || offset == Vec3::ZERO {
+            return;
+        }
+
+        let mut direction = offset.normalize();
+        if is_mouse_button_down(self.button) {
+            direction = turn(direction, up, -delta.x * self.mouse_sensitivity, delta.y * self.mouse_sensitivity);
+        }
+
+        let mut distance = offset.length();
+        let (_, wheel) = mouse_wheel();
+        if wheel != 0. {
+            distance *= (1. - self.zoom_speed).powf(wheel.signum());
+        }
+        let distance = distance.clamp(self.min_distance, self.max_distance);
+
+        camera.position = camera.target + direction * distance;
+    }
+}
+
 impl Camera for Camera3D {
     fn matrix(&self) -> Mat4 {
         let aspect = self.aspect.unwrap_or(screen_width() / screen_height());
@@ -253,7 +407,8 @@ pub fn set_camera(camera: &dyn Camera) {
     // flush previous camera draw calls
     context.perform_render_passes();
 
-    context.gl.render_pass(camera.render_pass().map(|rt| rt.raw_miniquad_id()));
+    let render_pass = camera.render_pass().map(|rt| rt.raw_miniquad_id());
+    context.gl.render_pass(render_pass.or_else(|| context.default_render_pass()));
 
     context.gl.viewport(camera.viewport());
     context.gl.depth_test(camera.depth_enabled());
@@ -267,7 +422,7 @@ pub fn set_default_camera() {
     // flush previous camera draw calls
     context.perform_render_passes();
 
-    context.gl.render_pass(None);
+    context.gl.render_pass(context.default_render_pass());
     context.gl.viewport(None);
     context.gl.depth_test(false);
     context.camera_matrix = None;
@@ -301,3 +456,73 @@ pub fn pop_camera_state() {
         context.camera_matrix = camera_state.matrix;
     }
 }
+
+/// Screen space position, in logical pixels like `mouse_position`, of a world space position of the active camera.
+///
+/// Works with whatever camera was last set, including ones restored by `pop_camera_state`.
+pub fn world_to_screen(point: Vec2) -> Vec2 {
+    let context = get_context();
+
+    project_to_area(context.projection_matrix(), active_screen_area(), point)
+}
+
+/// World space position of the active camera under a screen space position, often `mouse_position`.
+///
+/// Assumes the world is a plane at z = 0, as for `Camera2D`.
+pub fn screen_to_world(point: Vec2) -> Vec2 {
+    let context = get_context();
+
+    unproject_from_area(context.projection_matrix(), active_screen_area(), point)
+}
+
+/// Part of the screen, in logical pixels, the active camera draws to: its viewport or the whole screen.
+fn active_screen_area() -> Rect {
+    let context = get_context();
+    let dpi = miniquad::window::dpi_scale();
+
+    match context.gl.get_active_viewport() {
+        // viewports are in physical pixels, going up from the bottom of the screen
+        Some((x, y, w, h)) => Rect::new(
+            x as f32 / dpi,
+            (context.screen_height - (y + h) as f32) / dpi,
+            w as f32 / dpi,
+            h as f32 / dpi,
+        ),
+        None => Rect::new(0., 0., screen_width(), screen_height()),
+    }
+}
+
+fn project_to_area(matrix: Mat4, area: Rect, point: Vec2) -> Vec2 {
+    let ndc = matrix.project_point3(vec3(point.x, point.y, 0.));
+
+    vec2(area.x + (ndc.x + 1.) / 2. * area.w, area.y + (1. - ndc.y) / 2. * area.h)
+}
+
+fn unproject_from_area(matrix: Mat4, area: Rect, point: Vec2) -> Vec2 {
+    let ndc = vec2((point.x - area.x) / area.w * 2. - 1., 1. - (point.y - area.y) / area.h * 2.);
+    let world = matrix.inverse().project_point3(vec3(ndc.x, ndc.y, 0.));
+
+    vec2(world.x, world.y)
+}
+
+#[test]
+fn screen_world_round_trip() {
+    let camera = Camera2D {
+        rotation: 30.,
+        zoom: vec2(0.01, 0.02),
+        target: vec2(120., -40.),
+        offset: vec2(0.1, -0.2),
+        ..Default::default()
+    };
+    let area = Rect::new(0., 0., 800., 600.);
+
+    for point in [vec2(0., 0.), vec2(120., -40.), vec2(-350.5, 72.25), vec2(1000., 1000.)] {
+        let screen = project_to_area(camera.matrix(), area, point);
+        let back = unproject_from_area(camera.matrix(), area, screen);
+        assert!((back - point).length() < 1e-2, "{point} -> {screen} -> {back}");
+    }
+
+    // the target is drawn at the center of the screen, moved by the offset
+    let center = project_to_area(camera.matrix(), area, camera.target);
+    assert!((center - vec2(440., 360.)).length() < 1e-3, "{center}");
+}
@@ -35,6 +35,72 @@ fn color_from_bytes() {
     assert_eq!(Color::new(0.0, 1.0, 0.5, 1.0), color_u8!(0, 255, 127.5, 255));
 }
 
+#[test]
+fn color_from_hex_str() {
+    assert_eq!(Color::from_hex_str("#ff8800"), Some(Color::from_rgba(255, 136, 0, 255)));
+    assert_eq!(Color::from_hex_str("FF8800"), Some(Color::from_rgba(255, 136, 0, 255)));
+    assert_eq!(Color::from_hex_str("#Ff880040"), Some(Color::from_rgba(255, 136, 0, 64)));
+    assert_eq!(Color::from_rgba(255, 136, 0, 64).to_hex(), "#FF880040");
+
+    for invalid in ["", "#", "#ff880", "#ff88004", "#ff8800400", "ff88zz", "+ff8800", "##ff8800"] {
+        assert_eq!(Color::from_hex_str(invalid), None, "{invalid}");
+    }
+}
+
+#[test]
+fn color_hsv_hsl_round_trip() {
+    let close = |a: Color, b: Color| (a.to_vec() - b.to_vec()).abs().max_element() < 1e-4;
+
+    for r in 0..=4 {
+        for g in 0..=4 {
+            for b in 0..=4 {
+                let color = Color::new(r as f32 / 4., g as f32 / 4., b as f32 / 4., 1.);
+
+                let (h, s, v) = color.to_hsv();
+                assert!((0. ..360.).contains(&h), "{color:?} {h}");
+                assert!(close(Color::from_hsv(h, s, v), color), "{color:?} {:?}", (h, s, v));
+
+                let (h, s, l) = color.to_hsl();
+                assert!((0. ..360.).contains(&h), "{color:?} {h}");
+                assert!(close(Color::from_hsl(h, s, l), color), "{color:?} {:?}", (h, s, l));
+            }
+        }
+    }
+
+    assert!(close(Color::from_hsv(240., 1., 0.5), Color::new(0., 0., 0.5, 1.)));
+    assert!(close(Color::from_hsv(-60., 1., 1.), Color::from_hsv(300., 1., 1.)));
+    assert!(close(Color::from_hsv(720., 0., 0.3), Color::new(0.3, 0.3, 0.3, 1.)));
+    assert!(close(Color::from_hsl(480., 1., 0.5), Color::new(0., 1., 0., 1.)));
+    assert!(close(Color::from_hsl(90., 0., 0.7), Color::new(0.7, 0.7, 0.7, 1.)));
+}
+
+#[test]
+fn color_lerp_and_linear() {
+    let close = |a: Color, b: Color| (a.to_vec() - b.to_vec()).abs().max_element() < 1e-3;
+    let red = Color::new(1., 0., 0., 1.);
+    let blue = Color::new(0., 0., 1., 0.);
+
+    assert_eq!(red.lerp(blue, 0.), red);
+    assert_eq!(red.lerp(blue, 1.), blue);
+    assert!(close(red.lerp(blue, 0.25), Color::new(0.75, 0., 0.25, 0.75)));
+    assert_eq!(red.lerp(blue, -3.), red);
+    assert_eq!(red.lerp(blue, 5.), blue);
+
+    let srgb = Color::new(0.5, 0.02, 1., 0.3);
+    let linear = Color::new(0.214, 0.02 / 12.92, 1., 0.3);
+    assert!(close(srgb.to_linear(), linear));
+    assert!(close(Color::from_linear(linear), srgb));
+    for i in 0..=20 {
+        let color = Color::new(i as f32 / 20., 1. - i as f32 / 20., 0.5, 1.);
+        assert!(close(Color::from_linear(color.to_linear()), color), "{color:?}");
+    }
+
+    // halfway in linear space is brighter than the plain average
+    let middle = red.lerp_linear(blue, 0.5);
+    assert!(close(middle, Color::new(0.735, 0., 0.735, 0.5)), "{middle:?}");
+    assert!(close(red.lerp_linear(blue, 2.), blue));
+}
+
 impl From<Color> for [u8; 4] {
     fn from(val: Color) -> Self {
         [
@@ -115,6 +181,40 @@ impl Color {
         Self::from_rgba(bytes[1], bytes[2], bytes[3], 255)
     }
 
+    /// Parse a `RRGGBB` or `RRGGBBAA` hexadecimal string, optionally starting with `#`, as in `[#RRGGBB]` text markup.
+    /// Returns `None` if it's anything else.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use macroquad::prelude::*;
+    ///
+    /// assert_eq!(Color::from_hex_str("#3CA7D5"), Some(Color::from_hex(0x3CA7D5)));
+    /// assert_eq!(Color::from_hex_str("ff000080"), Some(Color::from_rgba(255, 0, 0, 128)));
+    /// assert_eq!(Color::from_hex_str("#FFF"), None);
+    /// ```
+    pub fn from_hex_str(hex: &str) -> Option<Color> {
+        let digits = hex.strip_prefix('#').unwrap_or(hex);
+        if !digits.bytes().all(|byte| byte.is_ascii_hexdigit()) {
+            return None;
+        }
+
+        let [r, g, b, a] = u32::from_str_radix(digits, 16).ok()?.to_be_bytes();
+        match digits.len() {
+            6 => Some(Color::from_rgba(g, b, a, 255)),
+            8 => Some(Color::from_rgba(r, g, b, a)),
+            _ => None,
+        }
+    }
+
+    /// Format as `#RRGGBBAA` with uppercase digits, the inverse of [Color::from_hex_str].
+    /// Components are clamped to 0.0..=1.0 and rounded to the nearest byte.
+    pub fn to_hex(&self) -> String {
+        let byte = |component: f32| (component.clamp(0., 1.) * 255.).round() as u8;
+
+        format!("#{:02X}{:02X}{:02X}{:02X}", byte(self.r), byte(self.g), byte(self.b), byte(self.a))
+    }
+
     /// Create a vec4 of red, green, blue, and alpha components.
     pub const fn to_vec(&self) -> glam::Vec4 {
         glam::Vec4::new(self.r, self.g, self.b, self.a)
@@ -129,6 +229,122 @@ impl Color {
     pub const fn with_alpha(&self, alpha: f32) -> Color {
         Color::new(self.r, self.g, self.b, alpha)
     }
+
+    /// Build an opaque color from hue in degrees, wrapped to 0..360, and saturation and value between 0.0 and 1.0.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use macroquad::prelude::*;
+    ///
+    /// assert_eq!(Color::from_hsv(120., 1., 1.), Color::new(0., 1., 0., 1.));
+    /// assert_eq!(Color::from_hsv(-240., 1., 1.), Color::from_hsv(120., 1., 1.));
+    /// ```
+    pub fn from_hsv(h: f32, s: f32, v: f32) -> Color {
+        let sector = h.rem_euclid(360.) / 60.;
+        let chroma = v * s;
+        let x = chroma * (1. - (sector % 2. - 1.).abs());
+        let m = v - chroma;
+
+        let (r, g, b) = match sector as u32 {
+            0 => (chroma, x, 0.),
+            1 => (x, chroma, 0.),
+            2 => (0., chroma, x),
+            3 => (0., x, chroma),
+            4 => (x, 0., chroma),
+            5 => (chroma, 0., x),
+            // 360 after rounding up a tiny negative hue
+            _ => (chroma, 0., 0.),
+        };
+
+        Color::new(r + m, g + m, b + m, 1.)
+    }
+
+    /// Hue in degrees from 0 to 360, saturation and value, the inverse of [Color::from_hsv]. Alpha is ignored.
+    ///
+    /// Hue is 0 for grays, and saturation is 0 for black.
+    pub fn to_hsv(&self) -> (f32, f32, f32) {
+        let max = self.r.max(self.g).max(self.b);
+        let min = self.r.min(self.g).min(self.b);
+        let delta = max - min;
+
+        let h = if delta == 0. {
+            0.
+        } else if max == self.r {
+            60. * ((self.g - self.b) / delta).rem_euclid(6.)
+        } else if max == self.g {
+            60. * ((self.b - self.r) / delta + 2.)
+        } else {
+            60. * ((self.r - self.g) / delta + 4.)
+        };
+        let s = if max == 0. { 0. } else { delta / max };
+
+        (h, s, max)
+    }
+
+    /// Build an opaque color from hue in degrees, wrapped to 0..360, and saturation and lightness between 0.0 and 1.0.
+    ///
+    /// Same as [hsl_to_rgb], which takes hue between 0.0 and 1.0.
+    pub fn from_hsl(h: f32, s: f32, l: f32) -> Color {
+        hsl_to_rgb(h.rem_euclid(360.) / 360., s, l)
+    }
+
+    /// Hue in degrees from 0 to 360, saturation and lightness, the inverse of [Color::from_hsl]. Alpha is ignored.
+    ///
+    /// Hue and saturation are 0 for grays.
+    pub fn to_hsl(&self) -> (f32, f32, f32) {
+        let (h, s, l) = rgb_to_hsl(*self);
+
+        ((h * 360.) % 360., s, l)
+    }
+
+    /// Blend component-wise from `self` at `t` = 0.0 to `other` at `t` = 1.0, alpha included.
+    /// `t` is clamped to 0.0..=1.0.
+    pub fn lerp(&self, other: Color, t: f32) -> Color {
+        Color::from_vec(self.to_vec().lerp(other.to_vec(), t.clamp(0., 1.)))
+    }
+
+    /// Same as [Color::lerp] but blending the linear intensities, see [Color::to_linear],
+    /// which keeps gradients between saturated colors from going dark in the middle.
+    pub fn lerp_linear(&self, other: Color, t: f32) -> Color {
+        Color::from_linear(self.to_linear().lerp(other.to_linear(), t))
+    }
+
+    /// Convert the sRGB components to linear intensities with the sRGB transfer curve. Alpha is kept as is.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use macroquad::prelude::*;
+    ///
+    /// let linear = Color::new(0.5, 0.5, 0.5, 0.5).to_linear();
+    /// assert!((linear.r - 0.214).abs() < 0.001);
+    /// assert_eq!(linear.a, 0.5);
+    /// ```
+    pub fn to_linear(&self) -> Color {
+        fn decode(c: f32) -> f32 {
+            if c <= 0.04045 {
+                c / 12.92
+            } else {
+                ((c + 0.055) / 1.055).powf(2.4)
+            }
+        }
+
+        Color::new(decode(self.r), decode(self.g), decode(self.b), self.a)
+    }
+
+    /// Convert linear intensities back to sRGB components, the inverse of [Color::to_linear]. Alpha is kept as is.
+    pub fn from_linear(linear: Color) -> Color {
+        fn encode(c: f32) -> f32 {
+            if c <= 0.0031308 {
+                c * 12.92
+            } else {
+                1.055 * c.powf(1. / 2.4) - 0.055
+            }
+        }
+
+        Color::new(encode(linear.r), encode(linear.g), encode(linear.b), linear.a)
+    }
 }
 
 pub mod colors {
@@ -0,0 +1,97 @@
+//! Immediate mode debug overlay, drawn on top of everything else at the end of the frame.
+
+use crate::{
+    color::{Color, WHITE},
+    get_context,
+    shapes::draw_rectangle,
+    text::{draw_text_ex, measure_text, TextParams},
+};
+
+const LINE_HEIGHT: f32 = 18.;
+const PADDING: f32 = 4.;
+
+#[derive(Default)]
+pub(crate) struct DebugOverlay {
+    enabled: bool,
+    lines: Vec<String>,
+    watches: Vec<(String, String)>,
+}
+
+impl DebugOverlay {
+    pub(crate) fn is_empty(&self) -> bool {
+        self.lines.is_empty() && self.watches.is_empty()
+    }
+
+    /// Draws this frame's lines and watches, then forgets them.
+    pub(crate) fn draw(&mut self) {
+        let lines = std::mem::take(&mut self.lines);
+        let watches = std::mem::take(&mut self.watches);
+
+        if !self.enabled || (lines.is_empty() && watches.is_empty()) {
+            return;
+        }
+
+        let text = lines
+            .into_iter()
+            .chain(watches.into_iter().map(|(name, value)| format!("{name}: {value}")))
+            .collect::<Vec<_>>();
+        let width = text
+            .iter()
+            .map(|line| measure_text(line, None, 16, 1.0, None).width)
+            .fold(0., f32::max);
+
+        draw_rectangle(
+            0.,
+            0.,
+            width + PADDING * 2.,
+            text.len() as f32 * LINE_HEIGHT + PADDING * 2.,
+            Color::new(0., 0., 0., 0.6),
+        );
+        for (i, line) in text.iter().enumerate() {
+            draw_text_ex(
+                line,
+                PADDING,
+                PADDING + (i + 1) as f32 * LINE_HEIGHT - 4.,
+                TextParams {
+                    color: WHITE,
+                    enable_markup: false,
+                    ..Default::default()
+                },
+            );
+        }
+    }
+}
+
+/// Show or hide the debug overlay. Hidden by default.
+pub fn set_debug_overlay(enabled: bool) {
+    get_context().debug_overlay.enabled = enabled;
+}
+
+/// Add a line to the debug overlay for the current frame.
+pub fn debug_print(text: &str) {
+    let overlay = &mut get_context().debug_overlay;
+
+    if overlay.enabled {
+        overlay.lines.push(text.to_owned());
+    }
+}
+
+/// Show `name: value` in the debug overlay for the current frame.
+/// Watching the same name again in a frame replaces its value.
+/// ```ignore
+/// debug_watch("fps", get_fps());
+/// debug_watch("player", player.pos);
+/// ```
+pub fn debug_watch(name: &str, value: impl std::fmt::Debug) {
+    let overlay = &mut get_context().debug_overlay;
+
+    if !overlay.enabled {
+        return;
+    }
+
+    let value = format!("{value:?}");
+    match overlay.watches.iter_mut().find(|(watched, _)| watched == name) {
+        Some((_, watched_value)) => *watched_value = value,
+        None => overlay.watches.push((name.to_owned(), value)),
+    }
+}
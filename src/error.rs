@@ -1,9 +1,20 @@
 #[derive(Debug)]
 pub enum Error {
     FontError(&'static str),
-    FileError { kind: miniquad::fs::Error, path: String },
+    FileError {
+        kind: miniquad::fs::Error,
+        path: String,
+    },
     ShaderError(miniquad::ShaderError),
     PngError(png::DecodingError),
+    #[cfg(feature = "jpeg")]
+    JpegError(jpeg_decoder::Error),
+    #[cfg(feature = "webp")]
+    WebpError(image_webp::DecodingError),
+    #[cfg(feature = "gif")]
+    GifError(gif::DecodingError),
+    /// The image is not PNG, or is JPEG/WebP while the matching feature is disabled.
+    UnsupportedImageFormat(&'static str),
     UnknownError(&'static str),
 }
 
@@ -25,6 +36,27 @@ impl From<png::DecodingError> for Error {
     }
 }
 
+#[cfg(feature = "jpeg")]
+impl From<jpeg_decoder::Error> for Error {
+    fn from(s: jpeg_decoder::Error) -> Self {
+        Error::JpegError(s)
+    }
+}
+
+#[cfg(feature = "webp")]
+impl From<image_webp::DecodingError> for Error {
+    fn from(s: image_webp::DecodingError) -> Self {
+        Error::WebpError(s)
+    }
+}
+
+#[cfg(feature = "gif")]
+impl From<gif::DecodingError> for Error {
+    fn from(s: gif::DecodingError) -> Self {
+        Error::GifError(s)
+    }
+}
+
 impl std::fmt::Display for Error {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         write!(f, "error: {self:?}")
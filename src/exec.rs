@@ -24,6 +24,21 @@ impl Future for FrameFuture {
     }
 }
 
+// Same as FrameFuture, but resolves to the duration of the frame that just completed.
+#[derive(Default)]
+pub struct TimedFrameFuture {
+    frame: FrameFuture,
+}
+
+impl Future for TimedFrameFuture {
+    type Output = f32;
+
+    fn poll(mut self: Pin<&mut Self>, context: &mut Context) -> Poll<Self::Output> {
+        // frame_time is updated at the end of Stage::draw, before the main future is resumed again.
+        Pin::new(&mut self.frame).poll(context).map(|()| crate::time::get_frame_time())
+    }
+}
+
 fn waker() -> Waker {
     unsafe fn clone(data: *const ()) -> RawWaker {
         RawWaker::new(data, &VTABLE)
@@ -0,0 +1,125 @@
+//! Named render passes queued during the frame and run in dependency order at its end.
+//!
+//! Immediate drawing stays the default, passes are for multi pass rendering where the order
+//! matters, e.g. a scene rendered into a target, post-processed into another, then shown with a HUD.
+
+use crate::{
+    camera::{set_camera, set_default_camera, Camera},
+    get_context,
+    texture::Texture2D,
+};
+
+/// A closure drawing with a camera, into the camera's render target or the screen.
+pub struct FramePass {
+    name: String,
+    camera: Option<Box<dyn Camera>>,
+    reads: Vec<Texture2D>,
+    draw: Box<dyn FnOnce()>,
+}
+
+impl FramePass {
+    /// Pass drawing with the default camera, to the screen.
+    pub fn new(name: &str, draw: impl FnOnce() + 'static) -> FramePass {
+        FramePass {
+            name: name.to_owned(),
+            camera: None,
+            reads: vec![],
+            draw: Box::new(draw),
+        }
+    }
+
+    /// Draw with `camera`, into its render target if it has one.
+    pub fn camera(mut self, camera: impl Camera + 'static) -> FramePass {
+        self.camera = Some(Box::new(camera));
+        self
+    }
+
+    /// Declare that this pass samples `texture`, so it runs after the passes rendering into it.
+    pub fn reads(mut self, texture: &Texture2D) -> FramePass {
+        self.reads.push(texture.clone());
+        self
+    }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn writes(&self) -> Option<miniquad::TextureId> {
+        let render_pass = self.camera.as_ref()?.render_pass()?;
+        Some(render_pass.color_texture.raw_miniquad_id())
+    }
+}
+
+/// Queue `pass` to run at the end of this frame, after everything drawn immediately.
+///
+/// Passes run in the order they were added, except that a pass reading a texture waits for
+/// the passes rendering into it. Passes in a dependency cycle keep their order.
+/// Camera, material, viewport and scissor are restored once all passes ran.
+pub fn add_frame_pass(pass: FramePass) {
+    get_context().frame_passes.push(pass);
+}
+
+/// Runs this frame's passes, called from `Context::end_frame`.
+pub(crate) fn run_frame_passes(passes: Vec<FramePass>) {
+    let writes = passes.iter().map(FramePass::writes).collect::<Vec<_>>();
+    let reads = passes
+        .iter()
+        .map(|pass| pass.reads.iter().map(Texture2D::raw_miniquad_id).collect())
+        .collect::<Vec<Vec<_>>>();
+    let order = pass_order(&writes, &reads);
+
+    let context = get_context();
+    crate::camera::push_camera_state();
+    let viewport = context.gl.get_active_viewport();
+    let pipeline = context.gl.get_pipeline();
+    let clip = context.gl.get_scissor();
+
+    let mut passes = passes.into_iter().map(Some).collect::<Vec<_>>();
+    for i in order {
+        let pass = passes[i].take().unwrap();
+        match &pass.camera {
+            Some(camera) => set_camera(camera.as_ref()),
+            None => set_default_camera(),
+        }
+        context.gl.pipeline(None);
+        context.gl.scissor(None);
+
+        (pass.draw)();
+    }
+
+    context.perform_render_passes();
+    context.gl.viewport(viewport);
+    context.gl.pipeline(pipeline);
+    context.gl.scissor(clip);
+    crate::camera::pop_camera_state();
+}
+
+/// Orders passes so each one comes after the passes writing what it reads,
+/// otherwise keeping the queue order. Passes left in a cycle are appended in queue order.
+fn pass_order<T: PartialEq>(writes: &[Option<T>], reads: &[Vec<T>]) -> Vec<usize> {
+    let depends_on =
+        |pass: usize, other: usize| other != pass && writes[other].as_ref().is_some_and(|written| reads[pass].contains(written));
+
+    let count = writes.len();
+    let mut order = Vec::with_capacity(count);
+    let mut done = vec![false; count];
+    while let Some(next) = (0..count).find(|&i| !done[i] && (0..count).all(|j| done[j] || !depends_on(i, j))) {
+        done[next] = true;
+        order.push(next);
+    }
+    order.extend((0..count).filter(|&i| !done[i]));
+    order
+}
+
+#[test]
+fn pass_order_follows_reads() {
+    // 0 reads what 2 writes, 1 is independent, 2 reads what 3 writes
+    let writes = [None, Some(10), Some(20), Some(30)];
+    let reads = [vec![20], vec![], vec![30], vec![]];
+    assert_eq!(pass_order(&writes, &reads), [1, 3, 2, 0]);
+
+    // a cycle keeps the queue order
+    let writes = [Some(1), Some(2)];
+    let reads = [vec![2], vec![1]];
+    assert_eq!(pass_order(&writes, &reads), [0, 1]);
+}
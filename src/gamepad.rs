@@ -0,0 +1,45 @@
+//! Gamepad related types.
+//!
+//! Macroquad doesn't read gamepads yet, so there is no per gamepad lookup: `GamepadKind::from_name`
+//! works on device names reported by a platform gamepad library until it does.
+
+/// Controller family, used to pick the button glyphs shown in prompts.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum GamepadKind {
+    Xbox,
+    PlayStation,
+    NintendoSwitch,
+    /// Unknown controller, usually shown with Xbox style glyphs.
+    Generic,
+}
+
+impl GamepadKind {
+    /// Infers the controller family from its device name as reported by the OS or the browser,
+    /// e.g. "Xbox Wireless Controller" or "054c-09cc-Wireless Controller".
+    pub fn from_name(name: &str) -> GamepadKind {
+        let name = name.to_lowercase();
+        let contains_any = |patterns: &[&str]| patterns.iter().any(|pattern| name.contains(pattern));
+
+        // USB vendor ids show up in names on the web and some linux drivers
+        if contains_any(&["xbox", "xinput", "x-box", "045e"]) {
+            GamepadKind::Xbox
+        } else if contains_any(&["playstation", "dualshock", "dualsense", "ps3", "ps4", "ps5", "054c"]) {
+            GamepadKind::PlayStation
+        } else if contains_any(&["nintendo", "switch", "joy-con", "pro controller", "057e"]) {
+            GamepadKind::NintendoSwitch
+        } else {
+            GamepadKind::Generic
+        }
+    }
+}
+
+#[test]
+fn kind_from_name() {
+    assert_eq!(GamepadKind::from_name("Xbox Wireless Controller"), GamepadKind::Xbox);
+    assert_eq!(GamepadKind::from_name("054c-09cc-Wireless Controller"), GamepadKind::PlayStation);
+    assert_eq!(
+        GamepadKind::from_name("Nintendo Switch Pro Controller"),
+        GamepadKind::NintendoSwitch
+    );
+    assert_eq!(GamepadKind::from_name("USB Gamepad"), GamepadKind::Generic);
+}
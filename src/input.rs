@@ -1,12 +1,14 @@
 //! Cross-platform mouse, keyboard (and gamepads soon) module.
 
-use std::collections::HashSet;
+use std::collections::{HashSet, VecDeque};
 
 use crate::get_context;
+use crate::logging::warn;
 use crate::prelude::screen_height;
 use crate::prelude::screen_width;
+use crate::texture::Texture2D;
 use crate::Vec2;
-pub use miniquad::{KeyCode, MouseButton};
+pub use miniquad::{CursorIcon, KeyCode, MouseButton};
 
 #[derive(Clone, Copy, Debug, Eq, PartialEq)]
 pub enum TouchPhase {
@@ -52,6 +54,25 @@ pub fn touches() -> Vec<Touch> {
     get_context().touches.clone()
 }
 
+/// Return the latest state of the touch with the given id, if it is active this frame.
+pub fn touch(id: u64) -> Option<Touch> {
+    get_context().touches.iter().rev().find(|touch| touch.id == id).cloned()
+}
+
+/// Return the position in pixels where the touch with the given id started,
+/// as long as it is active. Subtract it from the current position for the drag delta.
+pub fn touch_start_position(id: u64) -> Option<Vec2> {
+    let context = get_context();
+
+    // touches started this frame are only recorded at the end of it
+    context
+        .touches
+        .iter()
+        .find(|touch| touch.id == id && touch.phase == TouchPhase::Started)
+        .map(|touch| touch.position)
+        .or_else(|| context.touch_start_positions.get(&id).copied())
+}
+
 /// Returns whether the game window currently has focus.
 pub fn is_window_focused() -> bool {
     get_context().window_focused
@@ -76,15 +97,216 @@ pub fn clear_all_input_state() {
     }
 }
 
-pub fn mouse_wheel() -> (f32, f32) {
+/// Set the OS mouse cursor icon, e.g. `CursorIcon::Pointer` over buttons
+/// or `CursorIcon::Text` over text fields.
+/// Not supported on Android and iOS, where it only logs a warning.
+pub fn set_cursor_icon(icon: CursorIcon) {
+    if cfg!(any(target_os = "android", target_os = "ios")) {
+        warn!("set_cursor_icon is not supported on this platform");
+        return;
+    }
+
     let context = get_context();
+    if context.cursor_icon != icon {
+        miniquad::window::set_mouse_cursor(icon);
+        context.cursor_icon = icon;
+    }
+}
+
+/// Restore the default OS mouse cursor icon.
+pub fn reset_cursor_icon() {
+    set_cursor_icon(CursorIcon::Default);
+}
+
+/// Returns the cursor icon last set with `set_cursor_icon`.
+pub fn cursor_icon() -> CursorIcon {
+    get_context().cursor_icon
+}
+
+/// Replace the OS cursor with `texture` drawn at the mouse position on top of everything else,
+/// `hotspot` being the pixel of the texture pointing at the mouse position, e.g. the tip of an arrow.
+/// `None` shows the OS cursor again.
+///
+/// The texture is drawn at the end of the frame, so it lags the OS cursor by a frame.
+pub fn set_cursor_texture(cursor: Option<(&Texture2D, Vec2)>) {
+    let context = get_context();
+    context.cursor_texture = cursor.map(|(texture, hotspot)| (texture.clone(), hotspot));
+    miniquad::window::show_mouse(context.cursor_texture.is_none());
+}
+
+/// Grab the cursor, keeping it inside the window, e.g. for an FPS camera driven by `mouse_delta`.
+///
+/// The grab is released while the window is minimized and applied again when it is restored.
+pub fn set_cursor_grab(grab: bool) {
+    let context = get_context();
+    context.cursor_grabbed = grab;
+    miniquad::window::set_cursor_grab(grab);
+}
+
+/// Returns true if the cursor is grabbed with `set_cursor_grab`.
+pub fn is_cursor_grabbed() -> bool {
+    get_context().cursor_grabbed
+}
+
+/// Show or hide the OS cursor over the window.
+pub fn show_mouse(shown: bool) {
+    miniquad::window::show_mouse(shown);
+}
+
+/// Returns the mouse movement since the previous frame in logical pixels, (0, 0) if the mouse didn't move.
+///
+/// While the cursor is grabbed with `set_cursor_grab` this is the raw mouse motion reported by the OS
+/// instead, which is not limited by the window borders.
+pub fn mouse_delta() -> Vec2 {
+    let context = get_context();
+
+    if context.cursor_grabbed {
+        return context.mouse_raw_delta;
+    }
+    context
+        .last_frame_mouse_position
+        .map_or(Vec2::ZERO, |last| context._mouse_position - last)
+}
+
+/// Keep the keys and mouse buttons pressed or released and the mouse wheel movement of a frame
+/// until something reads them, instead of dropping them when the frame ends, off by default.
+///
+/// For event driven apps (`blocking_event_loop`) whose loop doesn't check input on every frame,
+/// e.g. while awaiting `wait_until`: clicks and key presses are kept until the next check.
+/// A frame reading any of them, with `is_key_pressed` or `mouse_wheel` for instance, clears all of them.
+pub fn set_keep_unread_input(keep: bool) {
+    get_context().keep_unread_input = keep;
+}
+
+/// Context for reading the pressed/released input of the frame, see [set_keep_unread_input].
+pub(crate) fn read_frame_input() -> &'static mut crate::Context {
+    let context = get_context();
+    context.frame_input_read = true;
+    context
+}
+
+pub fn mouse_wheel() -> (f32, f32) {
+    let context = read_frame_input();
 
     (context.mouse_wheel.x, context.mouse_wheel.y)
 }
 
+/// Detect if the button is being pressed
+pub fn is_mouse_button_down(btn: MouseButton) -> bool {
+    let context = get_context();
+
+    context.mouse_down.contains(&btn)
+}
+
+/// Detect if the button was down at the end of the previous frame.
+pub fn was_mouse_button_down_last_frame(btn: MouseButton) -> bool {
+    let context = get_context();
+
+    context.prev_mouse_down.contains(&btn)
+}
+
+/// Settings of the gamepad driven virtual cursor, see `update_gamepad_cursor`.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct GamepadCursorConf {
+    /// Cursor speed in pixels per second with the stick fully deflected.
+    pub sensitivity: f32,
+    /// Exponent of the stick response curve: 1.0 is linear,
+    /// higher values give more precision near the center and faster movement at full deflection.
+    pub acceleration: f32,
+    /// Stick deflection below which the cursor doesn't move.
+    pub dead_zone: f32,
+    /// How quickly the cursor velocity follows the stick, in the [0, 1] range per frame.
+    /// 1.0 disables smoothing.
+    pub smoothing: f32,
+}
+
+impl Default for GamepadCursorConf {
+    fn default() -> GamepadCursorConf {
+        GamepadCursorConf {
+            sensitivity: 800.,
+            acceleration: 2.,
+            dead_zone: 0.15,
+            smoothing: 0.3,
+        }
+    }
+}
+
+#[derive(Default)]
+pub(crate) struct GamepadCursor {
+    conf: GamepadCursorConf,
+    velocity: Vec2,
+    button_down: bool,
+}
+
+/// Configure the gamepad driven virtual cursor.
+pub fn set_gamepad_cursor_conf(conf: GamepadCursorConf) {
+    get_context().gamepad_cursor.conf = conf;
+}
+
+/// Move the virtual cursor with an analog stick `axis` (each component in the [-1, 1] range, y pointing down)
+/// and press the left mouse button while `click` is held. Call it once per frame.
+///
+/// The cursor drives the regular mouse position and left button state and events,
+/// so mouse based UI works with a gamepad unmodified.
+pub fn update_gamepad_cursor(axis: Vec2, click: bool) {
+    let context = get_context();
+    let cursor = &mut context.gamepad_cursor;
+    let conf = cursor.conf;
+
+    let deflection = axis.length().min(1.);
+    let target_velocity = if deflection <= conf.dead_zone {
+        Vec2::ZERO
+    } else {
+        let strength = ((deflection - conf.dead_zone) / (1. - conf.dead_zone)).powf(conf.acceleration);
+        axis / axis.length() * strength * conf.sensitivity
+    };
+    cursor.velocity += (target_velocity - cursor.velocity) * conf.smoothing.clamp(0., 1.);
+
+    // starts from the mouse position, so mouse and stick can be used interchangeably
+    if cursor.velocity != Vec2::ZERO {
+        let position = (context._mouse_position + cursor.velocity * context.frame_time as f32)
+            .clamp(Vec2::ZERO, Vec2::new(screen_width(), screen_height()));
+        context._mouse_position = position;
+        context.input_events.iter_mut().for_each(|arr| {
+            arr.push(crate::MiniquadInputEvent::MouseMotion {
+                x: position.x,
+                y: position.y,
+            })
+        });
+    }
+
+    let cursor = &mut context.gamepad_cursor;
+    if click != cursor.button_down {
+        cursor.button_down = click;
+        let (x, y) = (context._mouse_position.x, context._mouse_position.y);
+        let btn = MouseButton::Left;
+        if click {
+            context.mouse_down.insert(btn);
+            context.mouse_pressed.insert(btn);
+            context
+                .input_events
+                .iter_mut()
+                .for_each(|arr| arr.push(crate::MiniquadInputEvent::MouseButtonDown { x, y, btn }));
+        } else {
+            context.mouse_down.remove(&btn);
+            context.mouse_released.insert(btn);
+            context
+                .input_events
+                .iter_mut()
+                .for_each(|arr| arr.push(crate::MiniquadInputEvent::MouseButtonUp { x, y, btn }));
+        }
+    }
+}
+
+/// Returns the position of the gamepad driven virtual cursor, in logical pixels.
+/// As the virtual cursor moves the mouse position, this is the mouse position.
+pub fn gamepad_cursor_position() -> Vec2 {
+    get_context()._mouse_position
+}
+
 /// Detect if the key has been pressed once
 pub fn is_key_pressed(key_code: KeyCode) -> bool {
-    let context = get_context();
+    let context = read_frame_input();
 
     context.keys_pressed.contains(&key_code)
 }
@@ -98,34 +320,151 @@ pub fn is_key_down(key_code: KeyCode) -> bool {
 
 /// Detect if the key has been released this frame
 pub fn is_key_released(key_code: KeyCode) -> bool {
-    let context = get_context();
+    let context = read_frame_input();
 
     context.keys_released.contains(&key_code)
 }
 
-/// Return the last pressed char.
-/// Each "get_char_pressed" call will consume a character from the input queue.
+/// Detect if any of `key_codes` is being pressed, e.g. either an arrow or WASD.
+pub fn is_any_key_down(key_codes: &[KeyCode]) -> bool {
+    let context = get_context();
+
+    key_codes.iter().any(|key_code| context.keys_down.contains(key_code))
+}
+
+/// Detect if all of `key_codes` are being pressed, e.g. a chord like Ctrl+Shift+S.
+/// False for an empty slice.
+pub fn is_all_keys_down(key_codes: &[KeyCode]) -> bool {
+    let context = get_context();
+
+    !key_codes.is_empty() && key_codes.iter().all(|key_code| context.keys_down.contains(key_code))
+}
+
+/// Detect if any of `key_codes` has been pressed this frame.
+pub fn is_any_key_pressed(key_codes: &[KeyCode]) -> bool {
+    let context = read_frame_input();
+
+    key_codes.iter().any(|key_code| context.keys_pressed.contains(key_code))
+}
+
+/// Detect if the key was down at the end of the previous frame.
+///
+/// Together with `is_key_down` this gives edge detection that doesn't rely on
+/// the per frame pressed/released sets.
+pub fn was_key_down_last_frame(key_code: KeyCode) -> bool {
+    let context = get_context();
+
+    context.prev_keys_down.contains(&key_code)
+}
+
+/// Chars typed by the user, read in the order they were typed.
+#[derive(Default)]
+pub(crate) struct CharQueue {
+    chars: VecDeque<char>,
+}
+
+impl CharQueue {
+    pub(crate) fn push(&mut self, character: char) {
+        self.chars.push_back(character);
+    }
+
+    /// Removes the oldest char.
+    pub(crate) fn pop(&mut self) -> Option<char> {
+        self.chars.pop_front()
+    }
+
+    /// Removes all chars, oldest first.
+    pub(crate) fn drain(&mut self) -> impl Iterator<Item = char> + '_ {
+        self.chars.drain(..)
+    }
+
+    pub(crate) fn clear(&mut self) {
+        self.chars.clear();
+    }
+}
+
+#[test]
+fn char_queue_order() {
+    let mut queue = CharQueue::default();
+    "abc".chars().for_each(|c| queue.push(c));
+
+    assert_eq!(queue.pop(), Some('a'));
+    queue.push('d');
+    assert_eq!(queue.drain().collect::<String>(), "bcd");
+    assert_eq!(queue.pop(), None);
+}
+
+/// Keys pressed this frame, in the order they were pressed.
+#[derive(Default)]
+pub(crate) struct KeyPressOrder {
+    keys: Vec<KeyCode>,
+}
+
+impl KeyPressOrder {
+    /// Records `key_code` as the latest pressed key, a key pressed again moves to the end.
+    pub(crate) fn press(&mut self, key_code: KeyCode) {
+        self.remove(key_code);
+        self.keys.push(key_code);
+    }
+
+    pub(crate) fn remove(&mut self, key_code: KeyCode) {
+        self.keys.retain(|key| *key != key_code);
+    }
+
+    pub(crate) fn last(&self) -> Option<KeyCode> {
+        self.keys.last().copied()
+    }
+
+    pub(crate) fn clear(&mut self) {
+        self.keys.clear();
+    }
+}
+
+#[test]
+fn key_press_order() {
+    let mut order = KeyPressOrder::default();
+    [KeyCode::A, KeyCode::B, KeyCode::C].into_iter().for_each(|key| order.press(key));
+    assert_eq!(order.last(), Some(KeyCode::C));
+
+    order.press(KeyCode::A);
+    assert_eq!(order.last(), Some(KeyCode::A));
+    order.remove(KeyCode::A);
+    assert_eq!(order.last(), Some(KeyCode::C));
+    order.clear();
+    assert_eq!(order.last(), None);
+}
+
+/// Return the oldest pressed char not read yet.
+/// Each "get_char_pressed" call will consume a character from the input queue,
+/// so chars come out in the order they were typed.
 pub fn get_char_pressed() -> Option<char> {
     let context = get_context();
 
     context.chars_pressed_queue.pop()
 }
 
+/// Return all chars typed since the input queue was last read, in the order they were typed,
+/// consuming them. What a text field wants to append each frame.
+pub fn get_chars_this_frame() -> String {
+    let context = get_context();
+
+    context.chars_pressed_queue.drain().collect()
+}
+
 pub(crate) fn get_char_pressed_ui() -> Option<char> {
     let context = get_context();
 
     context.chars_pressed_ui_queue.pop()
 }
 
-/// Return the last pressed key.
+/// Return the key pressed last this frame.
 pub fn get_last_key_pressed() -> Option<KeyCode> {
-    let context = get_context();
-    // TODO: this will return a random key from keys_pressed HashMap instead of the last one, fix me later
-    context.keys_pressed.iter().next().cloned()
+    let context = read_frame_input();
+    context.keys_pressed_order.last()
 }
 
 pub fn get_keys_pressed() -> HashSet<KeyCode> {
-    let context = get_context();
+    let context = read_frame_input();
     context.keys_pressed.clone()
 }
 
@@ -135,7 +474,7 @@ pub fn get_keys_down() -> HashSet<KeyCode> {
 }
 
 pub fn get_keys_released() -> HashSet<KeyCode> {
-    let context = get_context();
+    let context = read_frame_input();
     context.keys_released.clone()
 }
 
@@ -161,12 +500,116 @@ pub fn is_quit_requested() -> bool {
     get_context().quit_requested
 }
 
+/// What to do with a quit request, returned by the [on_quit_requested] handler.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum QuitDecision {
+    /// Close the window, see [quit].
+    Confirm,
+    /// Keep the window open.
+    Cancel,
+}
+
+/// Call `handler` when the user tries to close the window, instead of closing it right away.
+///
+/// The handler runs outside of the main loop, so to ask the user first (e.g. "Save before exit?"),
+/// return [QuitDecision::Cancel], remember the request in shared state, show the prompt from the
+/// main loop and call [quit] once the user agreed.
+/// `is_quit_requested` is also true for the frame of the request.
+pub fn on_quit_requested(handler: impl FnMut() -> QuitDecision + 'static) {
+    get_context().quit_handler = Some(Box::new(handler));
+}
+
+/// Close the window and stop the application, without going through `prevent_quit` or [on_quit_requested].
+pub fn quit() {
+    miniquad::window::quit();
+}
+
 pub fn _debug_mouse_position() -> (f32, f32) {
     let context = get_context();
 
     (context._mouse_position.x, context._mouse_position.y)
 }
 
+/// Two finger gestures recognized from `touches`.
+///
+/// The gesture follows the two active touches with the lowest ids. Both report 0 when fewer
+/// than two touches are active and on the frame one of the two fingers is put down.
+pub mod gestures {
+    use std::collections::HashMap;
+
+    use super::{Touch, TouchPhase};
+    use crate::{get_context, Vec2};
+
+    /// Positions of the two gesture touches at the end of the previous frame and now.
+    fn gesture_pair(last_frame_positions: &HashMap<u64, Vec2>, touches: &[Touch]) -> Option<[(Vec2, Vec2); 2]> {
+        // the latest event of each touch tells whether it is still active
+        let mut latest: Vec<&Touch> = vec![];
+        for touch in touches {
+            match latest.iter_mut().find(|latest| latest.id == touch.id) {
+                Some(latest) => *latest = touch,
+                None => latest.push(touch),
+            }
+        }
+        latest.retain(|touch| touch.phase != TouchPhase::Ended && touch.phase != TouchPhase::Cancelled);
+        latest.sort_by_key(|touch| touch.id);
+
+        let [first, second, ..] = latest[..] else {
+            return None;
+        };
+        Some([
+            (*last_frame_positions.get(&first.id)?, first.position),
+            (*last_frame_positions.get(&second.id)?, second.position),
+        ])
+    }
+
+    fn deltas(last_frame_positions: &HashMap<u64, Vec2>, touches: &[Touch]) -> (f32, f32) {
+        let Some([(first_last, first), (second_last, second)]) = gesture_pair(last_frame_positions, touches) else {
+            return (0., 0.);
+        };
+
+        let last = second_last - first_last;
+        let now = second - first;
+        (now.length() - last.length(), last.perp_dot(now).atan2(last.dot(now)))
+    }
+
+    /// Returns how much the distance between the two touches changed since the previous frame, in pixels.
+    /// Positive when the fingers move apart, e.g. to zoom in.
+    pub fn pinch_delta() -> f32 {
+        let context = get_context();
+
+        deltas(&context.touch_last_frame_positions, &context.touches).0
+    }
+
+    /// Returns how much the line between the two touches turned since the previous frame, in radians
+    /// in the [-PI, PI] range. Positive is clockwise on screen, y pointing down.
+    pub fn rotation_delta() -> f32 {
+        let context = get_context();
+
+        deltas(&context.touch_last_frame_positions, &context.touches).1
+    }
+
+    #[test]
+    fn pinch_and_rotate() {
+        let touch = |id, phase, x, y| Touch {
+            id,
+            phase,
+            position: Vec2::new(x, y),
+        };
+        let last_frame = HashMap::from([(1, Vec2::new(0., 0.)), (2, Vec2::new(10., 0.))]);
+
+        let touches = [touch(1, TouchPhase::Stationary, 0., 0.), touch(2, TouchPhase::Moved, 0., 20.)];
+        let (pinch, rotation) = deltas(&last_frame, &touches);
+        assert_eq!(pinch, 10.);
+        assert!((rotation - std::f32::consts::FRAC_PI_2).abs() < 1e-6);
+
+        // a finger put down this frame or lifted doesn't count
+        let touches = [touch(1, TouchPhase::Stationary, 0., 0.), touch(3, TouchPhase::Started, 5., 5.)];
+        assert_eq!(deltas(&last_frame, &touches), (0., 0.));
+        let touches = [touch(1, TouchPhase::Stationary, 0., 0.), touch(2, TouchPhase::Ended, 20., 0.)];
+        assert_eq!(deltas(&last_frame, &touches), (0., 0.));
+    }
+}
+
 /// Functions for advanced input processing.
 ///
 /// Functions in this module should be used by external tools that uses miniquad system, like different UI libraries. User shouldn't use this function.
@@ -191,4 +634,54 @@ pub mod utils {
         }
         context.input_events[subscriber].clear();
     }
+
+    /// Mark `key` as handled by a subscriber: `is_key_pressed`, `is_key_down`, `is_key_released`
+    /// and the `get_keys_*` functions don't report it until it is pressed again.
+    ///
+    /// Call it after `repeat_all_miniquad_input` and before the game reads its input,
+    /// e.g. when a debug console reacts to its toggle key.
+    pub fn consume_key(key_code: crate::input::KeyCode) {
+        let context = get_context();
+
+        if context.keys_down.remove(&key_code) {
+            context.consumed_keys.insert(key_code);
+        }
+        context.keys_pressed.remove(&key_code);
+        context.keys_pressed_order.remove(key_code);
+        context.keys_released.remove(&key_code);
+    }
+
+    /// `consume_key` for all keys, and drops the typed chars not read yet.
+    /// What a subscriber having keyboard focus, like a text console, calls each frame.
+    pub fn consume_keyboard() {
+        let context = get_context();
+
+        context.consumed_keys.extend(context.keys_down.drain());
+        context.keys_pressed.clear();
+        context.keys_pressed_order.clear();
+        context.keys_released.clear();
+        context.chars_pressed_queue.clear();
+    }
+
+    /// Mark `btn` as handled by a subscriber: `is_mouse_button_down` doesn't report it until it is pressed again.
+    pub fn consume_mouse_button(btn: crate::input::MouseButton) {
+        let context = get_context();
+
+        if context.mouse_down.remove(&btn) {
+            context.consumed_mouse_buttons.insert(btn);
+        }
+        context.mouse_pressed.remove(&btn);
+        context.mouse_released.remove(&btn);
+    }
+
+    /// `consume_mouse_button` for all buttons, and drops this frame's mouse wheel movement,
+    /// e.g. while the mouse is over an overlay.
+    pub fn consume_mouse() {
+        let context = get_context();
+
+        context.consumed_mouse_buttons.extend(context.mouse_down.drain());
+        context.mouse_pressed.clear();
+        context.mouse_released.clear();
+        context.mouse_wheel = crate::math::Vec2::ZERO;
+    }
 }
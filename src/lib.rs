@@ -48,14 +48,20 @@ mod tobytes;
 
 pub mod camera;
 pub mod color;
+pub mod debug;
+pub mod frame_graph;
+pub mod gamepad;
 pub mod input;
 pub mod material;
 pub mod math;
 pub mod models;
+pub mod platform;
 pub mod shapes;
 pub mod text;
 pub mod texture;
+pub mod tilemap;
 pub mod time;
+pub mod ui;
 pub mod window;
 
 pub mod prelude;
@@ -135,6 +141,7 @@ pub use miniquad;
 
 use crate::{
     color::{colors::*, Color},
+    math::Rect,
     quad_gl::QuadGl,
     texture::TextureHandle,
 };
@@ -168,19 +175,54 @@ struct Context {
 
     keys_down: HashSet<KeyCode>,
     keys_pressed: HashSet<KeyCode>,
+    keys_pressed_order: input::KeyPressOrder,
     keys_released: HashSet<KeyCode>,
+    prev_keys_down: HashSet<KeyCode>,
     mouse_down: HashSet<MouseButton>,
     mouse_pressed: HashSet<MouseButton>,
     mouse_released: HashSet<MouseButton>,
+    prev_mouse_down: HashSet<MouseButton>,
+    // handled by an input subscriber, hidden from the game until pressed again
+    consumed_keys: HashSet<KeyCode>,
+    consumed_mouse_buttons: HashSet<MouseButton>,
     _mouse_position: Vec2,
+    cursor_icon: CursorIcon,
+    cursor_texture: Option<(texture::Texture2D, Vec2)>,
+    cursor_grabbed: bool,
+    // raw mouse motion accumulated this frame while the cursor is grabbed
+    mouse_raw_delta: Vec2,
+    // mouse position at the end of the previous frame, None until the mouse moved once
+    last_frame_mouse_position: Option<Vec2>,
+    gamepad_cursor: input::GamepadCursor,
     touches: Vec<input::Touch>,
-    chars_pressed_queue: Vec<char>,
-    chars_pressed_ui_queue: Vec<char>,
+    touch_start_positions: HashMap<u64, Vec2>,
+    // positions of the touches still active at the end of the previous frame, for gestures
+    touch_last_frame_positions: HashMap<u64, Vec2>,
+    chars_pressed_queue: input::CharQueue,
+    chars_pressed_ui_queue: input::CharQueue,
     mouse_wheel: Vec2,
+    keep_unread_input: bool,
+    frame_input_read: bool,
 
     prevent_quit_event: bool,
     quit_requested: bool,
+    quit_handler: Option<Box<dyn FnMut() -> input::QuitDecision>>,
+    exit_on_escape: bool,
     window_focused: bool,
+    window_minimized: bool,
+    window_drag_region: Option<Rect>,
+    window_drag_start: Option<Vec2>,
+    resize_handler: Option<Box<dyn FnMut(f32, f32)>>,
+    pixel_origin: window::Origin,
+    render_scale: f32,
+    render_scale_target: Option<texture::RenderTarget>,
+    dpi_scale: f32,
+    dpi_changed: bool,
+    frame_sync: bool,
+    // settings from `Conf` that miniquad can not report back, for `window::current_conf`
+    fullscreen: bool,
+    swap_interval: Option<i32>,
+    sample_count: i32,
 
     input_events: Vec<Vec<MiniquadInputEvent>>,
 
@@ -189,9 +231,19 @@ struct Context {
 
     pc_assets_folder: Option<String>,
 
-    start_time: f64,
+    clock: time::Clock,
     last_frame_time: f64,
     frame_time: f64,
+    smooth_frame_time: f64,
+    time_scale: f64,
+    fixed_timestep: f64,
+    fixed_accumulator: f64,
+    frame_count: u64,
+
+    debug_overlay: debug::DebugOverlay,
+    frame_passes: Vec<frame_graph::FramePass>,
+    frame_capture: Option<texture::FrameCapture>,
+    line_cap: shapes::LineCap,
 
     #[cfg(one_screenshot)]
     counter: usize,
@@ -204,6 +256,7 @@ struct Context {
     quad_context: Box<dyn miniquad::RenderingBackend>,
 
     default_filter_mode: crate::quad_gl::FilterMode,
+    default_font: Option<text::Font>,
     textures: crate::texture::TexturesContext,
 
     update_on: conf::UpdateTrigger,
@@ -301,19 +354,49 @@ impl Context {
 
             keys_down: HashSet::new(),
             keys_pressed: HashSet::new(),
+            keys_pressed_order: Default::default(),
             keys_released: HashSet::new(),
-            chars_pressed_queue: Vec::new(),
-            chars_pressed_ui_queue: Vec::new(),
+            prev_keys_down: HashSet::new(),
+            chars_pressed_queue: Default::default(),
+            chars_pressed_ui_queue: Default::default(),
             mouse_down: HashSet::new(),
             mouse_pressed: HashSet::new(),
             mouse_released: HashSet::new(),
+            prev_mouse_down: HashSet::new(),
+            consumed_keys: HashSet::new(),
+            consumed_mouse_buttons: HashSet::new(),
             touches: Vec::new(),
+            touch_start_positions: HashMap::new(),
+            touch_last_frame_positions: HashMap::new(),
             mouse_wheel: vec2(0., 0.),
+            keep_unread_input: false,
+            frame_input_read: false,
             _mouse_position: vec2(0., 0.),
+            cursor_grabbed: false,
+            mouse_raw_delta: vec2(0., 0.),
+            last_frame_mouse_position: None,
+            cursor_icon: CursorIcon::Default,
+            cursor_texture: None,
+            gamepad_cursor: Default::default(),
 
             prevent_quit_event: false,
             quit_requested: false,
+            quit_handler: None,
+            exit_on_escape: false,
             window_focused: true,
+            window_minimized: false,
+            window_drag_region: None,
+            window_drag_start: None,
+            resize_handler: None,
+            pixel_origin: window::Origin::TopLeft,
+            render_scale: 1.,
+            render_scale_target: None,
+            dpi_scale: 1.,
+            dpi_changed: false,
+            frame_sync: true,
+            fullscreen: false,
+            swap_interval: None,
+            sample_count: 1,
 
             input_events: Vec::new(),
 
@@ -325,9 +408,19 @@ impl Context {
 
             pc_assets_folder: None,
 
-            start_time: miniquad::date::now(),
+            clock: time::Clock::new(),
             last_frame_time: miniquad::date::now(),
             frame_time: 1. / 60.,
+            smooth_frame_time: 1. / 60.,
+            time_scale: 1.,
+            fixed_timestep: 1. / 60.,
+            fixed_accumulator: 0.,
+            frame_count: 0,
+
+            debug_overlay: debug::DebugOverlay::default(),
+            frame_passes: Vec::new(),
+            frame_capture: None,
+            line_cap: shapes::LineCap::Butt,
 
             #[cfg(one_screenshot)]
             counter: 0,
@@ -337,6 +430,7 @@ impl Context {
             quad_context: ctx,
 
             default_filter_mode,
+            default_font: None,
             textures: crate::texture::TexturesContext::new(),
             update_on,
 
@@ -361,17 +455,152 @@ impl Context {
     fn begin_frame(&mut self) {
         let color = Self::DEFAULT_BG_COLOR;
 
+        if self.frame_count == 0 {
+            // the window may have been resized between `Context::new` and the first frame,
+            // and the startup time should not count as the first frame time
+            (self.screen_width, self.screen_height) = miniquad::window::screen_size();
+            self.last_frame_time = self.clock.now();
+            self.dpi_scale = miniquad::window::dpi_scale();
+        }
+
+        // e.g. the window moved to a monitor with another scale factor
+        let dpi_scale = miniquad::window::dpi_scale();
+        self.dpi_changed = dpi_scale != self.dpi_scale;
+        self.dpi_scale = dpi_scale;
+
         get_quad_context().clear(Some((color.r, color.g, color.b, color.a)), None, None);
         self.gl.reset();
         self.gl.total_draw_calls = 0;
+
+        let previous_pass = self.default_render_pass();
+        self.update_render_scale_target();
+        let pass = self.default_render_pass();
+
+        // only follow the default framebuffer, passes of user render targets are left as is
+        if self.gl.get_active_render_pass() == previous_pass {
+            self.gl.render_pass(pass);
+        }
+        if let Some(pass) = pass {
+            let ctx = get_quad_context();
+            ctx.begin_pass(Some(pass), PassAction::clear_color(color.r, color.g, color.b, color.a));
+            ctx.end_render_pass();
+        }
+    }
+
+    /// Render pass used in place of the default framebuffer: the offscreen target of `window::set_render_scale`, if any.
+    pub(crate) fn default_render_pass(&self) -> Option<miniquad::RenderPass> {
+        self.render_scale_target.as_ref().map(|target| target.render_pass.raw_miniquad_id())
+    }
+
+    /// Keeps the offscreen target in sync with the render scale and the window size.
+    fn update_render_scale_target(&mut self) {
+        if self.render_scale == 1. {
+            self.render_scale_target = None;
+            return;
+        }
+
+        let width = ((self.screen_width * self.render_scale) as u32).max(1);
+        let height = ((self.screen_height * self.render_scale) as u32).max(1);
+        let up_to_date = self
+            .render_scale_target
+            .as_ref()
+            .is_some_and(|target| target.texture.width() as u32 == width && target.texture.height() as u32 == height);
+
+        if !up_to_date {
+            self.render_scale_target = Some(texture::render_target(width, height));
+        }
+    }
+
+    /// Upscales the offscreen target of `window::set_render_scale` to the window.
+    /// Camera, material, viewport and scissor are kept for the next frame.
+    fn present_render_scale_target(&mut self) {
+        let Some(target) = self.render_scale_target.clone() else {
+            return;
+        };
+
+        let render_pass = self.gl.get_active_render_pass();
+        let depth_test = self.gl.is_depth_test_enabled();
+        let viewport = self.gl.get_active_viewport();
+        let pipeline = self.gl.get_pipeline();
+        let clip = self.gl.get_scissor();
+
+        self.gl.render_pass(None);
+        self.gl.depth_test(false);
+        self.gl.viewport(None);
+        self.gl.pipeline(None);
+        self.gl.scissor(None);
+
+        let dpi = miniquad::window::dpi_scale();
+        texture::draw_texture_ex(
+            &target.texture,
+            0.,
+            0.,
+            color::WHITE,
+            texture::DrawTextureParams {
+                dest_size: Some(vec2(self.screen_width / dpi, self.screen_height / dpi)),
+                flip_y: true,
+                ..Default::default()
+            },
+        );
+        let screen_mat = self.pixel_perfect_projection_matrix();
+        self.gl.draw(get_quad_context(), screen_mat);
+
+        self.gl.render_pass(render_pass);
+        self.gl.depth_test(depth_test);
+        self.gl.viewport(viewport);
+        self.gl.pipeline(pipeline);
+        self.gl.scissor(clip);
+    }
+
+    /// Draws the debug overlay and the cursor texture on top of everything, with the default camera.
+    /// Camera, material, viewport and scissor are kept for the next frame.
+    fn draw_overlays(&mut self) {
+        crate::camera::push_camera_state();
+        let viewport = self.gl.get_active_viewport();
+        let pipeline = self.gl.get_pipeline();
+        let clip = self.gl.get_scissor();
+
+        crate::camera::set_default_camera();
+        self.gl.pipeline(None);
+        self.gl.scissor(None);
+
+        if !self.debug_overlay.is_empty() {
+            self.debug_overlay.draw();
+        }
+        if let Some((texture, hotspot)) = self.cursor_texture.clone() {
+            let position = self._mouse_position - hotspot;
+            texture::draw_texture(&texture, position.x, position.y, WHITE);
+        }
+
+        self.gl.viewport(viewport);
+        self.gl.pipeline(pipeline);
+        self.gl.scissor(clip);
+        crate::camera::pop_camera_state();
     }
 
     fn end_frame(&mut self) {
+        if !self.frame_passes.is_empty() {
+            frame_graph::run_frame_passes(std::mem::take(&mut self.frame_passes));
+        }
+        if !self.debug_overlay.is_empty() || self.cursor_texture.is_some() {
+            self.draw_overlays();
+        }
+
         self.perform_render_passes();
 
         let screen_mat = self.pixel_perfect_projection_matrix();
         self.gl.draw(get_quad_context(), screen_mat);
 
+        self.present_render_scale_target();
+
+        if let Some(mut capture) = self.frame_capture.take() {
+            capture.capture_frame();
+            // the capture may have been restarted from elsewhere meanwhile
+            if self.frame_capture.is_none() {
+                self.frame_capture = Some(capture);
+            }
+        }
+
         get_quad_context().commit_frame();
 
         #[cfg(one_screenshot)]
@@ -383,11 +612,22 @@ impl Context {
             }
         }
 
-        self.mouse_wheel = Vec2::new(0., 0.);
-        self.keys_pressed.clear();
-        self.keys_released.clear();
-        self.mouse_pressed.clear();
-        self.mouse_released.clear();
+        self.prev_keys_down.clone_from(&self.keys_down);
+        self.prev_mouse_down.clone_from(&self.mouse_down);
+
+        if self.frame_input_read || !self.keep_unread_input {
+            self.mouse_wheel = Vec2::new(0., 0.);
+            self.keys_pressed.clear();
+            self.keys_pressed_order.clear();
+            self.keys_released.clear();
+            self.mouse_pressed.clear();
+            self.mouse_released.clear();
+        }
+        self.frame_input_read = false;
+        self.mouse_raw_delta = Vec2::ZERO;
+        if self.last_frame_mouse_position.is_some() {
+            self.last_frame_mouse_position = Some(self._mouse_position);
+        }
 
         self.quit_requested = false;
 
@@ -399,12 +639,17 @@ impl Context {
         for touch in self.touches.iter() {
             if touch.phase == input::TouchPhase::Ended || touch.phase == input::TouchPhase::Cancelled {
                 map.remove(&touch.id);
+                self.touch_start_positions.remove(&touch.id);
                 continue;
             }
+            if touch.phase == input::TouchPhase::Started {
+                self.touch_start_positions.insert(touch.id, touch.position);
+            }
             map.insert(touch.id, touch.clone());
         }
 
         self.touches = map.into_values().collect();
+        self.touch_last_frame_positions = self.touches.iter().map(|touch| (touch.id, touch.position)).collect();
 
         // change all Started or Moved touches to Stationary
         for touch in &mut self.touches {
@@ -416,12 +661,35 @@ impl Context {
         self.dropped_files.clear();
     }
 
+    /// Goes through `prevent_quit` and `on_quit_requested` for a window close or `set_exit_on_escape`,
+    /// returns whether the application should actually quit.
+    fn handle_quit_request(&mut self) -> bool {
+        if let Some(mut handler) = self.quit_handler.take() {
+            self.quit_requested = true;
+
+            let decision = handler();
+            // the handler may have replaced itself
+            if self.quit_handler.is_none() {
+                self.quit_handler = Some(handler);
+            }
+            decision == input::QuitDecision::Confirm
+        } else if self.prevent_quit_event {
+            self.quit_requested = true;
+            false
+        } else {
+            true
+        }
+    }
+
     pub(crate) fn pixel_perfect_projection_matrix(&self) -> glam::Mat4 {
         let (width, height) = miniquad::window::screen_size();
 
         let dpi = miniquad::window::dpi_scale();
 
-        glam::Mat4::orthographic_rh_gl(0., width / dpi, height / dpi, 0., -1., 1.)
+        match self.pixel_origin {
+            window::Origin::TopLeft => glam::Mat4::orthographic_rh_gl(0., width / dpi, height / dpi, 0., -1., 1.),
+            window::Origin::BottomLeft => glam::Mat4::orthographic_rh_gl(0., width / dpi, 0., height / dpi, -1., 1.),
+        }
     }
 
     pub(crate) fn projection_matrix(&self) -> glam::Mat4 {
@@ -464,8 +732,18 @@ struct Stage {
 
 impl EventHandler for Stage {
     fn resize_event(&mut self, width: f32, height: f32) {
-        get_context().screen_width = width;
-        get_context().screen_height = height;
+        let context = get_context();
+        context.screen_width = width;
+        context.screen_height = height;
+
+        if let Some(mut handler) = context.resize_handler.take() {
+            let dpi = miniquad::window::dpi_scale();
+            handler(width / dpi, height / dpi);
+            // the handler may have replaced itself
+            if context.resize_handler.is_none() {
+                context.resize_handler = Some(handler);
+            }
+        }
 
         if miniquad::window::blocking_event_loop() {
             miniquad::window::schedule_update();
@@ -473,17 +751,11 @@ impl EventHandler for Stage {
     }
 
     fn raw_mouse_motion(&mut self, x: f32, y: f32) {
-        /*        let context = get_context();
+        let context = get_context();
 
         if context.cursor_grabbed {
-            context.mouse_position += Vec2::new(x, y);
-
-            let event = MiniquadInputEvent::MouseMotion {
-                x: context.mouse_position.x,
-                y: context.mouse_position.y,
-            };
-            context.input_events.iter_mut().for_each(|arr| arr.push(event.clone()));
-        }*/
+            context.mouse_raw_delta += Vec2::new(x, y);
+        }
     }
 
     fn mouse_motion_event(&mut self, x: f32, y: f32) {
@@ -499,8 +771,12 @@ impl EventHandler for Stage {
             .iter_mut()
             .for_each(|arr| arr.push(MiniquadInputEvent::MouseMotion { x: lx, y: ly }));
 
+        // the first motion only tells where the mouse is, it doesn't move it
+        context.last_frame_mouse_position.get_or_insert(Vec2::new(lx, ly));
         context._mouse_position = Vec2::new(lx, ly);
 
+        window::update_window_drag(x, y);
+
         // Generate touch events when simulate_touch_with_mouse is enabled
         // Only generate move events if the left mouse button is down
         if context.simulate_touch_with_mouse && context.mouse_down.contains(&MouseButton::Left) {
@@ -550,9 +826,14 @@ impl EventHandler for Stage {
         let lx = x / dpi;
         let ly = y / dpi;
 
+        context.consumed_mouse_buttons.remove(&btn);
         context.mouse_down.insert(btn);
         context.mouse_pressed.insert(btn);
 
+        if btn == MouseButton::Left {
+            window::begin_window_drag(x, y);
+        }
+
         context
             .input_events
             .iter_mut()
@@ -591,7 +872,13 @@ impl EventHandler for Stage {
         let ly = y / dpi;
 
         context.mouse_down.remove(&btn);
-        context.mouse_released.insert(btn);
+        if !context.consumed_mouse_buttons.remove(&btn) {
+            context.mouse_released.insert(btn);
+        }
+
+        if btn == MouseButton::Left {
+            window::end_window_drag();
+        }
 
         context
             .input_events
@@ -663,9 +950,19 @@ impl EventHandler for Stage {
 
     fn key_down_event(&mut self, keycode: KeyCode, modifiers: KeyMods, repeat: bool) {
         let context = get_context();
-        context.keys_down.insert(keycode);
-        if repeat == false {
-            context.keys_pressed.insert(keycode);
+        if !repeat {
+            context.consumed_keys.remove(&keycode);
+        }
+        if !context.consumed_keys.contains(&keycode) {
+            context.keys_down.insert(keycode);
+            if !repeat {
+                context.keys_pressed.insert(keycode);
+                context.keys_pressed_order.press(keycode);
+            }
+        }
+
+        if keycode == KeyCode::Escape && !repeat && context.exit_on_escape && context.handle_quit_request() {
+            input::quit();
         }
 
         context.input_events.iter_mut().for_each(|arr| {
@@ -688,7 +985,9 @@ impl EventHandler for Stage {
     fn key_up_event(&mut self, keycode: KeyCode, modifiers: KeyMods) {
         let context = get_context();
         context.keys_down.remove(&keycode);
-        context.keys_released.insert(keycode);
+        if !context.consumed_keys.remove(&keycode) {
+            context.keys_released.insert(keycode);
+        }
 
         context
             .input_events
@@ -702,7 +1001,10 @@ impl EventHandler for Stage {
 
     fn update(&mut self) {
         // Unless called every frame, cursor will not remain grabbed
-        //miniquad::window::set_cursor_grab(get_context().cursor_grabbed);
+        let context = get_context();
+        if context.cursor_grabbed && !context.window_minimized {
+            miniquad::window::set_cursor_grab(true);
+        }
 
         #[cfg(not(target_arch = "wasm32"))]
         {
@@ -730,21 +1032,27 @@ impl EventHandler for Stage {
             }
 
             #[cfg(any(target_arch = "wasm32", target_os = "linux"))]
-            {
+            if get_context().frame_sync {
                 unsafe {
                     miniquad::gl::glFlush();
                     miniquad::gl::glFinish();
                 }
             }
 
-            get_context().frame_time = date::now() - get_context().last_frame_time;
-            get_context().last_frame_time = date::now();
+            let now = get_context().clock.now();
+            get_context().frame_time = now - get_context().last_frame_time;
+            get_context().last_frame_time = now;
+            // exponential moving average, following a change of frame rate within a few dozen frames
+            get_context().smooth_frame_time += (get_context().frame_time - get_context().smooth_frame_time) * 0.1;
+            get_context().fixed_accumulator += get_context().frame_time * get_context().time_scale;
+            get_context().frame_count += 1;
         }
     }
 
     fn window_restored_event(&mut self) {
         let context = get_context();
         context.window_focused = true;
+        context.window_minimized = false;
 
         #[cfg(target_os = "android")]
         if miniquad::window::blocking_event_loop() {
@@ -760,6 +1068,12 @@ impl EventHandler for Stage {
     fn window_minimized_event(&mut self) {
         let context = get_context();
         context.window_focused = false;
+        context.window_minimized = true;
+
+        // Don't keep the cursor stuck in a minimized window, `update` grabs it again once restored
+        if context.cursor_grabbed {
+            miniquad::window::set_cursor_grab(false);
+        }
 
         // Clear held down keys and button and announce them as released
         context.mouse_released.extend(context.mouse_down.drain());
@@ -777,10 +1091,8 @@ impl EventHandler for Stage {
     }
 
     fn quit_requested_event(&mut self) {
-        let context = get_context();
-        if context.prevent_quit_event {
+        if !get_context().handle_quit_request() {
             miniquad::window::cancel_quit();
-            context.quit_requested = true;
         }
     }
 }
@@ -871,14 +1183,20 @@ impl Window {
             draw_call_vertex_capacity,
             draw_call_index_capacity,
         } = config.into();
+        let fullscreen = miniquad_conf.fullscreen;
+        let swap_interval = miniquad_conf.platform.swap_interval;
+        let sample_count = miniquad_conf.sample_count;
         miniquad::start(miniquad_conf, move || {
             thread_assert::set_thread_id();
-            let context = Context::new(
+            let mut context = Context::new(
                 update_on.unwrap_or_default(),
                 default_filter_mode,
                 draw_call_vertex_capacity,
                 draw_call_index_capacity,
             );
+            context.fullscreen = fullscreen;
+            context.swap_interval = swap_interval;
+            context.sample_count = sample_count;
             unsafe { CONTEXT = Some(context) };
 
             Box::new(Stage {
@@ -1,7 +1,13 @@
 //! Custom materials - shaders, uniforms.
 
-use crate::{get_context, quad_gl::GlPipeline, texture::Texture2D, tobytes::ToBytes, Error};
-use miniquad::{PipelineParams, UniformDesc};
+use crate::{
+    get_context,
+    quad_gl::GlPipeline,
+    texture::{RenderTarget, Texture2D},
+    tobytes::ToBytes,
+    Error,
+};
+use miniquad::{BlendFactor, BlendState, BlendValue, Equation, PipelineParams, UniformDesc, VertexAttribute};
 use std::sync::Arc;
 
 #[derive(PartialEq)]
@@ -40,6 +46,34 @@ impl Material {
     pub fn set_texture(&self, name: &str, texture: Texture2D) {
         get_context().gl.set_texture(self.pipeline.0, name, texture);
     }
+
+    /// Run `f` with all of its draw calls rendered with this material into `target`,
+    /// optionally clipped to `scissor` (in target pixels).
+    ///
+    /// The camera matrix is kept as is; the previous render target, material and
+    /// scissor are restored once `f` returns.
+    /// Useful to chain post-processing passes through intermediate render targets.
+    pub fn render_to<F: FnOnce()>(&self, target: &RenderTarget, scissor: Option<(i32, i32, i32, i32)>, f: F) {
+        let context = get_context();
+
+        let render_pass = context.gl.get_active_render_pass();
+        let pipeline = context.gl.get_pipeline();
+        let clip = context.gl.get_scissor();
+
+        // flush draw calls made for the previous target
+        context.perform_render_passes();
+        context.gl.render_pass(Some(target.render_pass.raw_miniquad_id()));
+        context.gl.pipeline(Some(self.pipeline.0));
+        context.gl.scissor(scissor);
+
+        f();
+
+        let context = get_context();
+        context.perform_render_passes();
+        context.gl.render_pass(render_pass);
+        context.gl.pipeline(pipeline);
+        context.gl.scissor(clip);
+    }
 }
 
 /// Params used for material loading.
@@ -56,6 +90,14 @@ pub struct MaterialParams {
 
     /// List of textures used in this material
     pub textures: Vec<String>,
+
+    /// Custom vertex attributes, read from the bytes of `Vertex::normal` instead of
+    /// the default `normal: Float4` attribute, e.g. `tangent: Float2` and `weights: Float2`
+    /// filled from `normal.xy` and `normal.zw`.
+    /// Their sizes should add up to exactly 16 bytes and each of them should be an input
+    /// of the vertex shader, otherwise `load_material` returns a `ShaderError`.
+    /// Empty by default, keeping the default vertex layout.
+    pub vertex_attributes: Vec<VertexAttribute>,
 }
 
 /// Creates custom material
@@ -126,6 +168,7 @@ pub fn load_material(shader: crate::ShaderSource, params: MaterialParams) -> Res
         params.pipeline_params,
         params.uniforms,
         params.textures,
+        params.vertex_attributes,
     )?;
 
     Ok(Material {
@@ -143,6 +186,24 @@ pub fn gl_use_default_material() {
     get_context().gl.pipeline(None);
 }
 
+/// Blending for textures with premultiplied alpha, to be used as `PipelineParams::color_blend`
+/// of custom materials drawing such textures.
+pub fn premultiplied_alpha_blend() -> BlendState {
+    BlendState::new(Equation::Add, BlendFactor::One, BlendFactor::OneMinusValue(BlendValue::SourceAlpha))
+}
+
+/// All following macroquad rendering calls will use the default material with
+/// `premultiplied_alpha_blend`, for textures made with `Texture2D::from_image_premultiplied`.
+/// Only triangles are supported, use `gl_use_default_material` to go back.
+///
+/// Note that tint colors should be premultiplied too, e.g. `Color::new(0.5, 0.5, 0.5, 0.5)` for half transparent white.
+pub fn gl_use_premultiplied_alpha_material() {
+    let gl = &mut get_context().gl;
+    let pipeline = gl.premultiplied_alpha_pipeline();
+
+    gl.pipeline(Some(pipeline));
+}
+
 #[doc(hidden)]
 pub mod shaders {
     type IncludeFilename = String;
@@ -6,9 +6,11 @@ pub use glam::*;
 
 mod circle;
 mod rect;
+mod transform;
 
 pub use circle::Circle;
 pub use rect::{Rect, RectOffset};
+pub use transform::Transform2D;
 
 /// Converts 2d polar coordinates to 2d cartesian coordinates.
 pub fn polar_to_cartesian(rho: f32, theta: f32) -> Vec2 {
@@ -20,6 +22,21 @@ pub fn cartesian_to_polar(cartesian: Vec2) -> Vec2 {
     vec2((cartesian.x.powi(2) + cartesian.y.powi(2)).sqrt(), cartesian.y.atan2(cartesian.x))
 }
 
+/// Angle helpers for [Vec2] on top of the ones glam already provides
+/// (`Vec2::from_angle`, `Vec2::to_angle` and `Vec2::angle_to`).
+pub trait Vec2Ext {
+    /// Returns the vector rotated by `radians`.
+    ///
+    /// Unlike `Vec2::rotate`, which takes a direction vector, this takes an angle.
+    fn rotate_by(self, radians: f32) -> Vec2;
+}
+
+impl Vec2Ext for Vec2 {
+    fn rotate_by(self, radians: f32) -> Vec2 {
+        Vec2::from_angle(radians).rotate(self)
+    }
+}
+
 /// Returns value, bounded in range [min, max].
 pub fn clamp<T: PartialOrd>(value: T, min: T, max: T) -> T {
     if value < min {
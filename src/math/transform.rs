@@ -0,0 +1,87 @@
+use crate::math::{Affine2, Mat4, Vec2, Vec4};
+
+/// Position, rotation and scale of a 2D object, composable into parent/child hierarchies,
+/// e.g. a turret placed relative to its tank.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Transform2D {
+    /// Where `pivot` ends up.
+    pub position: Vec2,
+    /// Rotation in radians around `pivot`.
+    pub rotation: f32,
+    pub scale: Vec2,
+    /// Local point rotated and scaled around, placed at `position`.
+    pub pivot: Vec2,
+}
+
+impl Default for Transform2D {
+    fn default() -> Transform2D {
+        Transform2D {
+            position: Vec2::ZERO,
+            rotation: 0.,
+            scale: Vec2::ONE,
+            pivot: Vec2::ZERO,
+        }
+    }
+}
+
+impl Transform2D {
+    pub fn new(position: Vec2, rotation: f32, scale: Vec2) -> Transform2D {
+        Transform2D {
+            position,
+            rotation,
+            scale,
+            pivot: Vec2::ZERO,
+        }
+    }
+
+    /// Transform of a child relative to this one, e.g. the tank for its turret.
+    ///
+    /// Exact as long as this transform's scale is uniform or the child is not rotated,
+    /// otherwise the shear the combination would need can't be represented and is dropped.
+    pub fn combine(&self, parent: &Transform2D) -> Transform2D {
+        Transform2D {
+            position: parent.transform_point(self.position),
+            rotation: parent.rotation + self.rotation,
+            scale: parent.scale * self.scale,
+            pivot: self.pivot,
+        }
+    }
+
+    /// Maps a local point to the parent space.
+    pub fn transform_point(&self, point: Vec2) -> Vec2 {
+        self.to_affine().transform_point2(point)
+    }
+
+    pub fn to_affine(&self) -> Affine2 {
+        Affine2::from_scale_angle_translation(self.scale, self.rotation, self.position) * Affine2::from_translation(-self.pivot)
+    }
+
+    /// The same transform as a 3D matrix, e.g. for `Mesh` vertices or a camera.
+    pub fn to_matrix(&self) -> Mat4 {
+        let affine = self.to_affine();
+
+        Mat4::from_cols(
+            affine.matrix2.x_axis.extend(0.).extend(0.),
+            affine.matrix2.y_axis.extend(0.).extend(0.),
+            Vec4::Z,
+            affine.translation.extend(0.).extend(1.),
+        )
+    }
+}
+
+#[test]
+fn transform_combine() {
+    let tank = Transform2D::new(Vec2::new(100., 50.), std::f32::consts::FRAC_PI_2, Vec2::splat(2.));
+    let turret = Transform2D {
+        pivot: Vec2::new(4., 4.),
+        ..Transform2D::new(Vec2::new(10., 0.), 0.5, Vec2::ONE)
+    };
+
+    let world = turret.combine(&tank);
+    let matrix = world.to_matrix();
+    for point in [Vec2::new(0., 0.), Vec2::new(4., 4.), Vec2::new(-3., 7.)] {
+        let expected = tank.transform_point(turret.transform_point(point));
+        assert!(world.transform_point(point).abs_diff_eq(expected, 1e-4));
+        assert!(matrix.transform_point3(point.extend(0.)).truncate().abs_diff_eq(expected, 1e-4));
+    }
+}
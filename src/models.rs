@@ -1,9 +1,10 @@
 //! 3D shapes and models, loading 3d models from files, drawing 3D primitives.
 
-use crate::{color::Color, get_context};
+use crate::{color::Color, get_context, get_quad_context};
 
 use crate::{quad_gl::DrawMode, texture::Texture2D};
-use glam::{vec2, vec3, vec4, Quat, Vec2, Vec3, Vec4};
+use glam::{vec2, vec3, vec4, Mat4, Quat, Vec2, Vec3, Vec4};
+use miniquad::{BufferId, BufferSource, BufferType, BufferUsage};
 
 #[repr(C)]
 #[derive(Clone, Debug, Copy)]
@@ -52,6 +53,66 @@ pub fn draw_mesh(mesh: &Mesh) {
     context.gl.geometry(&mesh.vertices[..], &mesh.indices[..]);
 }
 
+/// Same as `draw_mesh`, with the vertex colors multiplied by `tint`, e.g. to flash a shared model red on hit.
+/// The mesh itself is not modified and the draw batches like `draw_mesh`.
+pub fn draw_mesh_tinted(mesh: &Mesh, tint: Color) {
+    let context = get_context();
+
+    context.gl.texture(mesh.texture.as_ref());
+    context.gl.draw_mode(DrawMode::Triangles);
+    context.gl.geometry_tinted(&mesh.vertices[..], &mesh.indices[..], tint);
+}
+
+/// Geometry uploaded to the GPU once and drawn without being streamed again every frame,
+/// e.g. a whole tilemap baked at load time. Complements `draw_mesh` for content that rarely changes.
+///
+/// The batch owns its GPU buffers: they are freed when it is dropped, once the draws already
+/// queued with it are done. Changing the geometry means building a new batch.
+pub struct StaticBatch {
+    vertex_buffer: BufferId,
+    index_buffer: BufferId,
+    indices_count: usize,
+    pub texture: Option<Texture2D>,
+}
+
+impl StaticBatch {
+    /// Uploads `vertices` and `indices` (triangles), indices being `u16` a batch holds up to 65536 vertices.
+    pub fn build(vertices: &[Vertex], indices: &[u16]) -> StaticBatch {
+        let ctx = get_quad_context();
+        let vertex_buffer = ctx.new_buffer(BufferType::VertexBuffer, BufferUsage::Immutable, BufferSource::slice(vertices));
+        let index_buffer = ctx.new_buffer(BufferType::IndexBuffer, BufferUsage::Immutable, BufferSource::slice(indices));
+
+        StaticBatch {
+            vertex_buffer,
+            index_buffer,
+            indices_count: indices.len(),
+            texture: None,
+        }
+    }
+
+    pub fn with_texture(mut self, texture: &Texture2D) -> StaticBatch {
+        self.texture = Some(texture.clone());
+        self
+    }
+}
+
+impl Drop for StaticBatch {
+    fn drop(&mut self) {
+        get_context().gl.delete_buffers([self.vertex_buffer, self.index_buffer]);
+    }
+}
+
+/// Draws `batch` moved by `transform`, with the current camera and material, like `draw_mesh` but without uploading it.
+pub fn draw_static_batch(batch: &StaticBatch, transform: Mat4) {
+    let context = get_context();
+
+    context.gl.texture(batch.texture.as_ref());
+    context.gl.draw_mode(DrawMode::Triangles);
+    context
+        .gl
+        .static_geometry(batch.vertex_buffer, batch.index_buffer, batch.indices_count, transform);
+}
+
 fn draw_quad(vertices: [Vertex; 4]) {
     let context = get_context();
     let indices = [0, 1, 2, 0, 2, 3];
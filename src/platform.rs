@@ -0,0 +1,65 @@
+//! Queries about the device running the game, where the OS exposes them.
+
+/// Power source of the device, see [power_state].
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum PowerState {
+    /// No battery information on this platform, e.g. web, or a desktop without battery.
+    Unknown,
+    /// Running on battery, with the remaining charge in the [0, 1] range if known.
+    OnBattery(Option<f32>),
+    /// Plugged in, with the battery charge in the [0, 1] range if there is a battery.
+    PluggedIn(Option<f32>),
+}
+
+/// Returns whether the device runs on battery and how much charge is left,
+/// e.g. to lower the frame rate or effects on battery.
+///
+/// Only implemented on Linux, through `/sys/class/power_supply`, [PowerState::Unknown] elsewhere.
+/// Reads a few files, so better called once in a while than every frame.
+pub fn power_state() -> PowerState {
+    #[cfg(target_os = "linux")]
+    {
+        linux::power_state()
+    }
+    #[cfg(not(target_os = "linux"))]
+    {
+        PowerState::Unknown
+    }
+}
+
+#[cfg(target_os = "linux")]
+mod linux {
+    use super::PowerState;
+    use std::{fs, path::Path};
+
+    fn read(supply: &Path, name: &str) -> Option<String> {
+        fs::read_to_string(supply.join(name)).ok().map(|value| value.trim().to_owned())
+    }
+
+    pub(super) fn power_state() -> PowerState {
+        let Ok(supplies) = fs::read_dir("/sys/class/power_supply") else {
+            return PowerState::Unknown;
+        };
+
+        let mut battery = None;
+        let mut discharging = false;
+        let mut mains_online = false;
+        for supply in supplies.flatten().map(|entry| entry.path()) {
+            match read(&supply, "type").as_deref() {
+                Some("Battery") => {
+                    discharging |= read(&supply, "status").as_deref() == Some("Discharging");
+                    let capacity = read(&supply, "capacity").and_then(|capacity| capacity.parse::<f32>().ok());
+                    battery = battery.or(Some(capacity.map(|percent| percent / 100.)));
+                }
+                Some("Mains") => mains_online |= read(&supply, "online").as_deref() == Some("1"),
+                _ => {}
+            }
+        }
+
+        match battery {
+            None => PowerState::Unknown,
+            Some(charge) if discharging && !mains_online => PowerState::OnBattery(charge),
+            Some(charge) => PowerState::PluggedIn(charge),
+        }
+    }
+}
@@ -1,13 +1,18 @@
 //! Most common types that can be glob-imported `use macroquad::prelude::*` for convenience.
 
 pub use crate::camera::*;
+pub use crate::debug::*;
+pub use crate::frame_graph::*;
+pub use crate::gamepad::*;
 pub use crate::input::*;
 pub use crate::material::*;
 pub use crate::math::*;
 pub use crate::models::*;
+pub use crate::platform::*;
 pub use crate::shapes::*;
 pub use crate::text::*;
 pub use crate::texture::*;
+pub use crate::tilemap::*;
 pub use crate::time::*;
 pub use crate::window::*;
 
@@ -36,6 +36,8 @@ struct DrawCall {
     uniforms: Option<Vec<u8>>,
     render_pass: Option<RenderPass>,
     capture: bool,
+    /// Vertex and index buffers of a `StaticBatch`, drawn in place of the streamed geometry.
+    static_buffers: Option<(BufferId, BufferId)>,
 }
 
 impl DrawCall {
@@ -61,6 +63,7 @@ impl DrawCall {
             uniforms,
             render_pass,
             capture: false,
+            static_buffers: None,
         }
     }
 }
@@ -358,6 +361,7 @@ impl PipelinesStorage {
     const LINES_PIPELINE: GlPipeline = GlPipeline(1);
     const TRIANGLES_DEPTH_PIPELINE: GlPipeline = GlPipeline(2);
     const LINES_DEPTH_PIPELINE: GlPipeline = GlPipeline(3);
+    const PREMULTIPLIED_ALPHA_PIPELINE: GlPipeline = GlPipeline(4);
 
     fn new(ctx: &mut dyn RenderingBackend) -> PipelinesStorage {
         let shader = ctx
@@ -389,12 +393,15 @@ impl PipelinesStorage {
         };
 
         let triangles_pipeline = storage.make_pipeline(
-            ctx,
-            shader,
-            PipelineParams {
-                primitive_type: PrimitiveType::Triangles,
-                ..params
-            },
+            new_pipeline(
+                ctx,
+                shader,
+                PipelineParams {
+                    primitive_type: PrimitiveType::Triangles,
+                    ..params
+                },
+                &[],
+            ),
             false,
             vec![],
             vec![],
@@ -402,12 +409,15 @@ impl PipelinesStorage {
         assert_eq!(triangles_pipeline, Self::TRIANGLES_PIPELINE);
 
         let lines_pipeline = storage.make_pipeline(
-            ctx,
-            shader,
-            PipelineParams {
-                primitive_type: PrimitiveType::Lines,
-                ..params
-            },
+            new_pipeline(
+                ctx,
+                shader,
+                PipelineParams {
+                    primitive_type: PrimitiveType::Lines,
+                    ..params
+                },
+                &[],
+            ),
             false,
             vec![],
             vec![],
@@ -415,14 +425,17 @@ impl PipelinesStorage {
         assert_eq!(lines_pipeline, Self::LINES_PIPELINE);
 
         let triangles_depth_pipeline = storage.make_pipeline(
-            ctx,
-            shader,
-            PipelineParams {
-                depth_write: true,
-                depth_test: Comparison::LessOrEqual,
-                primitive_type: PrimitiveType::Triangles,
-                ..params
-            },
+            new_pipeline(
+                ctx,
+                shader,
+                PipelineParams {
+                    depth_write: true,
+                    depth_test: Comparison::LessOrEqual,
+                    primitive_type: PrimitiveType::Triangles,
+                    ..params
+                },
+                &[],
+            ),
             false,
             vec![],
             vec![],
@@ -430,44 +443,50 @@ impl PipelinesStorage {
         assert_eq!(triangles_depth_pipeline, Self::TRIANGLES_DEPTH_PIPELINE);
 
         let lines_depth_pipeline = storage.make_pipeline(
-            ctx,
-            shader,
-            PipelineParams {
-                depth_write: true,
-                depth_test: Comparison::LessOrEqual,
-                primitive_type: PrimitiveType::Lines,
-                ..params
-            },
+            new_pipeline(
+                ctx,
+                shader,
+                PipelineParams {
+                    depth_write: true,
+                    depth_test: Comparison::LessOrEqual,
+                    primitive_type: PrimitiveType::Lines,
+                    ..params
+                },
+                &[],
+            ),
             false,
             vec![],
             vec![],
         );
         assert_eq!(lines_depth_pipeline, Self::LINES_DEPTH_PIPELINE);
 
+        let premultiplied_alpha_pipeline = storage.make_pipeline(
+            new_pipeline(
+                ctx,
+                shader,
+                PipelineParams {
+                    color_blend: Some(crate::material::premultiplied_alpha_blend()),
+                    primitive_type: PrimitiveType::Triangles,
+                    ..params
+                },
+                &[],
+            ),
+            false,
+            vec![],
+            vec![],
+        );
+        assert_eq!(premultiplied_alpha_pipeline, Self::PREMULTIPLIED_ALPHA_PIPELINE);
+
         storage
     }
 
     fn make_pipeline(
         &mut self,
-        ctx: &mut dyn RenderingBackend,
-        shader: ShaderId,
-        params: PipelineParams,
+        pipeline: Pipeline,
         wants_screen_texture: bool,
         mut uniforms: Vec<UniformDesc>,
         textures: Vec<String>,
     ) -> GlPipeline {
-        let pipeline = ctx.new_pipeline(
-            &[BufferLayout::default()],
-            &[
-                VertexAttribute::new("position", VertexFormat::Float3),
-                VertexAttribute::new("texcoord", VertexFormat::Float2),
-                VertexAttribute::new("color0", VertexFormat::Byte4),
-                VertexAttribute::new("normal", VertexFormat::Float4),
-            ],
-            shader,
-            params,
-        );
-
         let id = self
             .pipelines
             .iter()
@@ -535,7 +554,6 @@ pub struct QuadGl {
     draw_calls_bindings: Vec<Bindings>,
     draw_calls_count: usize,
     state: GlState,
-    start_time: f64,
 
     pub(crate) white_texture: miniquad::TextureId,
     max_vertices: usize,
@@ -544,6 +562,73 @@ pub struct QuadGl {
     batch_vertex_buffer: Vec<Vertex>,
     batch_index_buffer: Vec<u16>,
     pub total_draw_calls: usize,
+    /// Buffers of dropped static batches, deleted once the draw calls queued with them are drawn.
+    deleted_buffers: Vec<BufferId>,
+}
+
+fn new_pipeline(
+    ctx: &mut dyn RenderingBackend,
+    shader: ShaderId,
+    params: PipelineParams,
+    vertex_attributes: &[VertexAttribute],
+) -> Pipeline {
+    let mut attributes = vec![
+        VertexAttribute::new("position", VertexFormat::Float3),
+        VertexAttribute::new("texcoord", VertexFormat::Float2),
+        VertexAttribute::new("color0", VertexFormat::Byte4),
+    ];
+    if vertex_attributes.is_empty() {
+        attributes.push(VertexAttribute::new("normal", VertexFormat::Float4));
+    } else {
+        attributes.extend_from_slice(vertex_attributes);
+    }
+
+    ctx.new_pipeline(
+        &[BufferLayout {
+            stride: std::mem::size_of::<Vertex>() as i32,
+            ..Default::default()
+        }],
+        &attributes,
+        shader,
+        params,
+    )
+}
+
+/// Custom vertex attributes are read from the bytes of `Vertex::normal`,
+/// so they have to exactly fill it and be declared by the vertex shader.
+fn validate_vertex_attributes(shader: &ShaderSource, vertex_attributes: &[VertexAttribute]) -> Result<(), ShaderError> {
+    if vertex_attributes.is_empty() {
+        return Ok(());
+    }
+
+    let size: i32 = vertex_attributes.iter().map(|attribute| attribute.format.size_bytes()).sum();
+    let expected = std::mem::size_of::<glam::Vec4>() as i32;
+    if size != expected {
+        return Err(ShaderError::LinkError(format!(
+            "custom vertex attributes take {size} bytes, they should take exactly {expected} bytes, the size of Vertex::normal"
+        )));
+    }
+
+    let vertex = match shader {
+        ShaderSource::Glsl { vertex, .. } => *vertex,
+        ShaderSource::Msl { program } => *program,
+    };
+    for attribute in vertex_attributes {
+        if ["position", "texcoord", "color0"].contains(&attribute.name) {
+            return Err(ShaderError::LinkError(format!(
+                "custom vertex attribute `{}` uses a name reserved by macroquad",
+                attribute.name
+            )));
+        }
+        if !vertex.contains(attribute.name) {
+            return Err(ShaderError::LinkError(format!(
+                "custom vertex attribute `{}` is not declared in the vertex shader",
+                attribute.name
+            )));
+        }
+    }
+
+    Ok(())
 }
 
 impl QuadGl {
@@ -569,7 +654,6 @@ impl QuadGl {
             draw_calls: Vec::with_capacity(200),
             draw_calls_bindings: Vec::with_capacity(200),
             draw_calls_count: 0,
-            start_time: miniquad::date::now(),
 
             white_texture,
             batch_vertex_buffer: Vec::with_capacity(max_vertices),
@@ -578,6 +662,7 @@ impl QuadGl {
             max_indices,
 
             total_draw_calls: 0,
+            deleted_buffers: vec![],
         }
     }
 
@@ -588,9 +673,12 @@ impl QuadGl {
         params: PipelineParams,
         uniforms: Vec<UniformDesc>,
         textures: Vec<String>,
+        vertex_attributes: Vec<VertexAttribute>,
     ) -> Result<GlPipeline, Error> {
         let mut shader_meta: ShaderMeta = shader::meta();
 
+        validate_vertex_attributes(&shader, &vertex_attributes)?;
+
         for uniform in &uniforms {
             shader_meta.uniforms.uniforms.push(uniform.clone());
         }
@@ -613,9 +701,12 @@ impl QuadGl {
         };
         let wants_screen_texture = source.contains("_ScreenTexture");
         let shader = ctx.new_shader(shader, shader_meta)?;
-        Ok(self
-            .pipelines
-            .make_pipeline(ctx, shader, params, wants_screen_texture, uniforms, textures))
+        Ok(self.pipelines.make_pipeline(
+            new_pipeline(ctx, shader, params, &vertex_attributes),
+            wants_screen_texture,
+            uniforms,
+            textures,
+        ))
     }
 
     pub(crate) fn clear(&mut self, ctx: &mut dyn miniquad::RenderingBackend, color: Color) {
@@ -670,7 +761,7 @@ impl QuadGl {
         assert_eq!(self.draw_calls_bindings.len(), self.draw_calls.len());
 
         let (screen_width, screen_height) = miniquad::window::screen_size();
-        let time = (miniquad::date::now() - self.start_time) as f32;
+        let time = crate::time::get_time() as f32;
         let time = glam::vec4(time, time.sin(), time.cos(), 0.);
 
         for (dc, bindings) in self.draw_calls[0..self.draw_calls_count]
@@ -697,14 +788,16 @@ impl QuadGl {
                 ctx.begin_default_pass(PassAction::Nothing);
             }
 
-            ctx.buffer_update(
-                bindings.vertex_buffers[0],
-                BufferSource::slice(&self.batch_vertex_buffer[dc.vertices_start..(dc.vertices_start + dc.vertices_count)]),
-            );
-            ctx.buffer_update(
-                bindings.index_buffer,
-                BufferSource::slice(&self.batch_index_buffer[dc.indices_start..(dc.indices_start + dc.indices_count)]),
-            );
+            if dc.static_buffers.is_none() {
+                ctx.buffer_update(
+                    bindings.vertex_buffers[0],
+                    BufferSource::slice(&self.batch_vertex_buffer[dc.vertices_start..(dc.vertices_start + dc.vertices_count)]),
+                );
+                ctx.buffer_update(
+                    bindings.index_buffer,
+                    BufferSource::slice(&self.batch_index_buffer[dc.indices_start..(dc.indices_start + dc.indices_count)]),
+                );
+            }
 
             bindings.images[0] = dc.texture.unwrap_or(white_texture);
             bindings.images[1] = self.state.snapshotter.screen_texture.unwrap_or(white_texture);
@@ -727,7 +820,13 @@ impl QuadGl {
             } else {
                 ctx.apply_scissor_rect(0, 0, width as i32, height as i32);
             }
+            let streamed_buffers = (bindings.vertex_buffers[0], bindings.index_buffer);
+            if let Some((vertex_buffer, index_buffer)) = dc.static_buffers {
+                bindings.vertex_buffers[0] = vertex_buffer;
+                bindings.index_buffer = index_buffer;
+            }
             ctx.apply_bindings(bindings);
+            (bindings.vertex_buffers[0], bindings.index_buffer) = streamed_buffers;
 
             if let Some(ref uniforms) = dc.uniforms {
                 for i in 0..uniforms.len() {
@@ -747,6 +846,10 @@ impl QuadGl {
             dc.indices_start = 0;
         }
 
+        for buffer in self.deleted_buffers.drain(..) {
+            ctx.delete_buffer(buffer);
+        }
+
         self.total_draw_calls += self.draw_calls_count;
         self.draw_calls_count = 0;
         self.batch_index_buffer.clear();
@@ -798,10 +901,18 @@ impl QuadGl {
         self.state.clip = clip;
     }
 
+    pub fn get_scissor(&self) -> Option<(i32, i32, i32, i32)> {
+        self.state.clip
+    }
+
     pub fn viewport(&mut self, viewport: Option<(i32, i32, i32, i32)>) {
         self.state.viewport = viewport;
     }
 
+    pub fn get_active_viewport(&self) -> Option<(i32, i32, i32, i32)> {
+        self.state.viewport
+    }
+
     pub fn get_viewport(&self) -> (i32, i32, i32, i32) {
         self.state
             .viewport
@@ -817,11 +928,26 @@ impl QuadGl {
         self.state.pipeline = pipeline;
     }
 
+    /// Default pipeline with blending for premultiplied alpha textures.
+    pub fn premultiplied_alpha_pipeline(&self) -> GlPipeline {
+        PipelinesStorage::PREMULTIPLIED_ALPHA_PIPELINE
+    }
+
+    pub fn get_pipeline(&self) -> Option<GlPipeline> {
+        self.state.pipeline
+    }
+
     pub fn draw_mode(&mut self, mode: DrawMode) {
         self.state.draw_mode = mode;
     }
 
     pub fn geometry(&mut self, vertices: &[Vertex], indices: &[u16]) {
+        self.geometry_tinted(vertices, indices, crate::color::WHITE);
+    }
+
+    /// Same as `geometry`, with the vertex colors multiplied by `tint` as they are copied into the batch.
+    /// The given vertices are left untouched.
+    pub fn geometry_tinted(&mut self, vertices: &[Vertex], indices: &[u16], tint: Color) {
         if vertices.len() >= self.max_vertices || indices.len() >= self.max_indices {
             warn!("geometry() exceeded max drawcall size, clamping");
         }
@@ -878,6 +1004,7 @@ impl QuadGl {
             self.draw_calls[self.draw_calls_count].pipeline = pip;
             self.draw_calls[self.draw_calls_count].render_pass = self.state.render_pass;
             self.draw_calls[self.draw_calls_count].capture = self.state.capture;
+            self.draw_calls[self.draw_calls_count].static_buffers = None;
             self.draw_calls[self.draw_calls_count].indices_start = self.batch_index_buffer.len();
             self.draw_calls[self.draw_calls_count].vertices_start = self.batch_vertex_buffer.len();
 
@@ -889,7 +1016,15 @@ impl QuadGl {
         let indices_len = indices.len();
         let vertices_len = vertices.len();
 
-        self.batch_vertex_buffer.extend(vertices);
+        if tint == crate::color::WHITE {
+            self.batch_vertex_buffer.extend(vertices);
+        } else {
+            let tint: [f32; 4] = tint.into();
+            self.batch_vertex_buffer.extend(vertices.iter().map(|vertex| Vertex {
+                color: std::array::from_fn(|i| (vertex.color[i] as f32 * tint[i]).round() as u8),
+                ..*vertex
+            }));
+        }
         self.batch_index_buffer
             .extend(indices.into_iter().map(|x| *x + dc.vertices_count as u16));
 
@@ -899,6 +1034,48 @@ impl QuadGl {
         dc.texture = self.state.texture;
     }
 
+    /// Queue a draw of the `indices_count` indices already uploaded to `vertex_buffer` and `index_buffer`,
+    /// with the current state and `transform` applied before the current model matrix.
+    pub(crate) fn static_geometry(&mut self, vertex_buffer: BufferId, index_buffer: BufferId, indices_count: usize, transform: glam::Mat4) {
+        let pip = self
+            .state
+            .pipeline
+            .unwrap_or_else(|| self.pipelines.get(self.state.draw_mode, self.state.depth_test_enable));
+        let uniforms = self
+            .state
+            .pipeline
+            .map(|pipeline| self.pipelines.get_quad_pipeline_mut(pipeline).uniforms_data.clone());
+
+        let mut draw_call = DrawCall::new(
+            self.state.texture,
+            self.state.model() * transform,
+            self.state.draw_mode,
+            pip,
+            uniforms,
+            self.state.render_pass,
+        );
+        draw_call.clip = self.state.clip;
+        draw_call.viewport = self.state.viewport;
+        draw_call.capture = self.state.capture;
+        draw_call.indices_count = indices_count;
+        draw_call.static_buffers = Some((vertex_buffer, index_buffer));
+
+        if self.draw_calls_count >= self.draw_calls.len() {
+            self.draw_calls.push(draw_call);
+        } else {
+            self.draw_calls[self.draw_calls_count] = draw_call;
+        }
+        self.draw_calls_count += 1;
+
+        // the following geometry can't be appended to this draw call
+        self.state.break_batching = true;
+    }
+
+    /// Delete `buffers` once the draw calls already queued are drawn, they may still use them.
+    pub(crate) fn delete_buffers(&mut self, buffers: impl IntoIterator<Item = BufferId>) {
+        self.deleted_buffers.extend(buffers);
+    }
+
     pub fn delete_pipeline(&mut self, pipeline: GlPipeline) {
         self.pipelines.delete_pipeline(pipeline);
     }
@@ -1,6 +1,6 @@
 //! 2D shapes rendering.
 
-use crate::{color::Color, get_context};
+use crate::{color::Color, get_context, math::Rect};
 
 use crate::quad_gl::{DrawMode, Vertex};
 use glam::{vec2, vec3, vec4, Mat4, Vec2};
@@ -181,6 +181,78 @@ pub fn draw_rectangle_ex(x: f32, y: f32, w: f32, h: f32, params: DrawRectanglePa
     context.gl.geometry(&vertices, &indices);
 }
 
+/// Draws a solid rectangle with its top-left corner at `[x, y]` with size `[w, h]` and its corners
+/// rounded by `radius`, clamped to half the smaller side, with a given `color`.
+pub fn draw_rectangle_rounded(x: f32, y: f32, w: f32, h: f32, radius: f32, color: Color) {
+    draw_rectangle_rounded_corners(x, y, w, h, [radius; 4], color);
+}
+
+/// Same as [draw_rectangle_rounded] with a radius per corner, going clockwise from the top left one:
+/// top left, top right, bottom right and bottom left.
+pub fn draw_rectangle_rounded_corners(x: f32, y: f32, w: f32, h: f32, radii: [f32; 4], color: Color) {
+    let context = get_context();
+
+    let rect = Rect::new(x, y, w, h);
+    let segments = corner_segments(radii.into_iter().fold(0., f32::max));
+    let points = rounded_rect_corner_points(rect, radii, segments);
+
+    let z = context.gl.draw_depth();
+    let center = rect.center();
+    let mut vertices = Vec::with_capacity(points.len() + 1);
+    vertices.push(Vertex::new(center.x, center.y, z, 0.5, 0.5, color));
+    for p in &points {
+        let uv = (*p - rect.point()) / rect.size();
+        vertices.push(Vertex::new(p.x, p.y, z, uv.x, uv.y, color));
+    }
+
+    let count = points.len() as u16;
+    let mut indices = Vec::with_capacity(points.len() * 3);
+    for i in 0..count {
+        indices.extend_from_slice(&[0, 1 + i, 1 + (i + 1) % count]);
+    }
+
+    context.gl.texture(None);
+    context.gl.draw_mode(DrawMode::Triangles);
+    context.gl.geometry(&vertices, &indices);
+}
+
+/// Draws the outline of a rectangle with its top-left corner at `[x, y]` with size `[w, h]` and its corners
+/// rounded by `radius`, with a given line `thickness` and `color`. The line is drawn inside of the rectangle.
+pub fn draw_rectangle_rounded_lines(x: f32, y: f32, w: f32, h: f32, radius: f32, thickness: f32, color: Color) {
+    let context = get_context();
+
+    let rect = Rect::new(x, y, w, h);
+    let radius = radius.clamp(0., w.min(h) / 2.);
+    let thickness = thickness.clamp(0., w.min(h) / 2.);
+    let inner_rect = Rect::new(x + thickness, y + thickness, w - thickness * 2., h - thickness * 2.);
+
+    // the inner corners share the centers of the outer ones while they are round
+    let segments = corner_segments(radius);
+    let outer = rounded_rect_points(rect, radius, segments);
+    let inner = rounded_rect_points(inner_rect, radius - thickness, segments);
+
+    let z = context.gl.draw_depth();
+    let mut vertices = Vec::with_capacity(outer.len() * 2);
+    vertices.extend(outer.iter().map(|p| Vertex::new(p.x, p.y, z, 0., 0., color)));
+    vertices.extend(inner.iter().map(|p| Vertex::new(p.x, p.y, z, 0., 0., color)));
+
+    let count = outer.len() as u16;
+    let mut indices = Vec::with_capacity(outer.len() * 6);
+    for i in 0..count {
+        let next = (i + 1) % count;
+        indices.extend_from_slice(&[i, next, count + next, i, count + next, count + i]);
+    }
+
+    context.gl.texture(None);
+    context.gl.draw_mode(DrawMode::Triangles);
+    context.gl.geometry(&vertices, &indices);
+}
+
+/// Steps per corner of a rounded rectangle, about one every 4 units along the arc.
+fn corner_segments(radius: f32) -> usize {
+    (radius * std::f32::consts::FRAC_PI_2 / 4.).ceil().clamp(1., 16.) as usize
+}
+
 /// Draws an outlined solid hexagon centered at `[x, y]` with a radius `size`, outline thickness
 /// defined by `border`, orientation defined by `vertical` (when `true`, the hexagon points along
 /// the `y` axis), and colors for outline given by `border_color` and fill by `fill_color`.
@@ -192,6 +264,48 @@ pub fn draw_hexagon(x: f32, y: f32, size: f32, border: f32, vertical: bool, bord
     }
 }
 
+/// Draws the soft shadow of `rect` moved by `offset`, fading out over `blur` units around its edges,
+/// e.g. behind UI cards and panels. Draw it before the rectangle casting it.
+///
+/// The blur is a linear fade from `blur / 2` inside the edges to `blur / 2` outside of them,
+/// drawn as a single mesh, so it is cheap and works with any camera.
+pub fn draw_rectangle_shadow(rect: Rect, blur: f32, offset: Vec2, color: Color) {
+    const SEGMENTS: usize = 6;
+
+    let context = get_context();
+
+    let rect = rect.offset(offset);
+    let blur = blur.max(0.);
+    // small rects are fully covered by the fade
+    let inset = (blur / 2.).min(rect.w / 2.).min(rect.h / 2.).max(0.);
+    let inner = Rect::new(rect.x + inset, rect.y + inset, rect.w - inset * 2., rect.h - inset * 2.);
+    let spread = inset + blur / 2.;
+    let outer = Rect::new(inner.x - spread, inner.y - spread, inner.w + spread * 2., inner.h + spread * 2.);
+
+    let z = context.gl.draw_depth();
+    let transparent = Color { a: 0., ..color };
+    let inner = rounded_rect_points(inner, 0., SEGMENTS);
+    let outer = rounded_rect_points(outer, spread, SEGMENTS);
+
+    let mut vertices = Vec::with_capacity(inner.len() * 2);
+    vertices.extend(inner.iter().map(|p| Vertex::new(p.x, p.y, z, 0., 0., color)));
+    vertices.extend(outer.iter().map(|p| Vertex::new(p.x, p.y, z, 0., 0., transparent)));
+
+    let count = inner.len() as u16;
+    let mut indices = Vec::with_capacity(inner.len() * 9);
+    for i in 1..count - 1 {
+        indices.extend_from_slice(&[0, i, i + 1]);
+    }
+    for i in 0..count {
+        let next = (i + 1) % count;
+        indices.extend_from_slice(&[i, next, count + next, i, count + next, count + i]);
+    }
+
+    context.gl.texture(None);
+    context.gl.draw_mode(DrawMode::Triangles);
+    context.gl.geometry(&vertices, &indices);
+}
+
 /// Draws a solid regular polygon centered at `[x, y]` with a given number of `sides`, `radius`,
 /// clockwise `rotation` (in degrees) and `color`.
 pub fn draw_poly(x: f32, y: f32, sides: u8, radius: f32, rotation: f32, color: Color) {
@@ -308,8 +422,33 @@ pub fn draw_ellipse_lines(x: f32, y: f32, w: f32, h: f32, rotation: f32, thickne
     }
 }
 
+/// How the ends of lines drawn with `draw_line` look, see [set_line_cap].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum LineCap {
+    /// The line stops right at its end points.
+    #[default]
+    Butt,
+    /// Half a circle past each end point, e.g. for smooth joints between chained lines.
+    Round,
+    /// The line goes on for half its thickness past each end point.
+    Square,
+}
+
+/// Set the end caps of the lines drawn with `draw_line` from now on, `LineCap::Butt` by default.
+pub fn set_line_cap(cap: LineCap) {
+    get_context().line_cap = cap;
+}
+
+pub fn line_cap() -> LineCap {
+    get_context().line_cap
+}
+
 /// Draws a line between points `[x1, y1]` and `[x2, y2]` with a given `thickness` and `color`.
+///
+/// The ends are drawn as set with [set_line_cap].
 pub fn draw_line(x1: f32, y1: f32, x2: f32, y2: f32, thickness: f32, color: Color) {
+    const CAP_SEGMENTS: u16 = 8;
+
     let context = get_context();
     let dx = x2 - x1;
     let dy = y2 - y1;
@@ -326,17 +465,387 @@ pub fn draw_line(x1: f32, y1: f32, x2: f32, y2: f32, thickness: f32, color: Colo
     let tx = nx / tlen;
     let ty = ny / tlen;
 
+    let cap = context.line_cap;
+    // (ty, -tx) goes from the start to the end, half the thickness long
+    let (x1, y1, x2, y2) = match cap {
+        LineCap::Square => (x1 - ty, y1 + tx, x2 + ty, y2 - tx),
+        _ => (x1, y1, x2, y2),
+    };
+
+    let quad = [
+        Vertex::new(x1 + tx, y1 + ty, 0., 0., 0., color),
+        Vertex::new(x1 - tx, y1 - ty, 0., 0., 0., color),
+        Vertex::new(x2 + tx, y2 + ty, 0., 0., 0., color),
+        Vertex::new(x2 - tx, y2 - ty, 0., 0., 0., color),
+    ];
+    let quad_indices = [0, 1, 2, 2, 1, 3];
+
+    context.gl.texture(None);
+    context.gl.draw_mode(DrawMode::Triangles);
+    if cap != LineCap::Round {
+        context.gl.geometry(&quad, &quad_indices);
+        return;
+    }
+
+    let mut vertices = quad.to_vec();
+    let mut indices = quad_indices.to_vec();
+    let normal_angle = ty.atan2(tx);
+    let radius = thickness * 0.5;
+    // the start cap turns from the normal through the backward direction, the end cap through the forward one
+    for (x, y, sweep) in [(x1, y1, std::f32::consts::PI), (x2, y2, -std::f32::consts::PI)] {
+        let center = vertices.len() as u16;
+        vertices.push(Vertex::new(x, y, 0., 0., 0., color));
+        for i in 0..=CAP_SEGMENTS {
+            let point = vec2(x, y) + radius * Vec2::from_angle(normal_angle + sweep * i as f32 / CAP_SEGMENTS as f32);
+            vertices.push(Vertex::new(point.x, point.y, 0., 0., 0., color));
+            if i != CAP_SEGMENTS {
+                indices.extend_from_slice(&[center, center + 1 + i, center + 2 + i]);
+            }
+        }
+    }
+
+    context.gl.geometry(&vertices, &indices);
+}
+
+/// Draws a batch of independent line segments, each going from `.0` to `.1`, with a given
+/// `thickness` and `color`.
+///
+/// All segments are submitted as a few large geometry chunks instead of one quad per call,
+/// which makes it a lot cheaper than calling `draw_line` in a loop for plots and overlays.
+pub fn draw_lines(lines: &[(Vec2, Vec2)], thickness: f32, color: Color) {
+    // Keeps each chunk well below the default draw call capacity (10000 vertices, 5000 indices).
+    const LINES_PER_CHUNK: usize = 512;
+
+    let context = get_context();
+    context.gl.texture(None);
+    context.gl.draw_mode(DrawMode::Triangles);
+
+    let mut vertices = Vec::<Vertex>::with_capacity(LINES_PER_CHUNK.min(lines.len()) * 4);
+    let mut indices = Vec::<u16>::with_capacity(LINES_PER_CHUNK.min(lines.len()) * 6);
+
+    for chunk in lines.chunks(LINES_PER_CHUNK) {
+        vertices.clear();
+        indices.clear();
+
+        for (start, end) in chunk {
+            let nx = start.y - end.y;
+            let ny = end.x - start.x;
+
+            let tlen = (nx * nx + ny * ny).sqrt() / (thickness * 0.5);
+            if tlen < f32::EPSILON {
+                continue;
+            }
+            let tx = nx / tlen;
+            let ty = ny / tlen;
+
+            indices.extend([0, 1, 2, 2, 1, 3].map(|k| k + vertices.len() as u16));
+            vertices.extend([
+                Vertex::new(start.x + tx, start.y + ty, 0., 0., 0., color),
+                Vertex::new(start.x - tx, start.y - ty, 0., 0., 0., color),
+                Vertex::new(end.x + tx, end.y + ty, 0., 0., 0., color),
+                Vertex::new(end.x - tx, end.y - ty, 0., 0., 0., color),
+            ]);
+        }
+
+        context.gl.geometry(&vertices, &indices);
+    }
+}
+
+/// Draws a quadratic Bézier curve from `p0` to `p2` bent towards `p1`, with a given `thickness` and `color`.
+///
+/// The curve is split into a number of segments that follows its length, see [draw_bezier_quad_segments].
+pub fn draw_bezier_quad(p0: Vec2, p1: Vec2, p2: Vec2, thickness: f32, color: Color) {
+    let segments = curve_segments(&[p0, p1, p2]);
+    draw_bezier_quad_segments(p0, p1, p2, segments, thickness, color);
+}
+
+/// Same as [draw_bezier_quad] with the curve split into `segments` straight segments.
+pub fn draw_bezier_quad_segments(p0: Vec2, p1: Vec2, p2: Vec2, segments: usize, thickness: f32, color: Color) {
+    let segments = segments.max(1);
+    let points: Vec<Vec2> = (0..=segments)
+        .map(|i| {
+            let t = i as f32 / segments as f32;
+            let u = 1. - t;
+            u * u * p0 + 2. * u * t * p1 + t * t * p2
+        })
+        .collect();
+
+    draw_polyline(&points, false, thickness, color);
+}
+
+/// Draws a cubic Bézier curve from `p0` to `p3` with control points `p1` and `p2`, with a given
+/// `thickness` and `color`.
+///
+/// The curve is split into a number of segments that follows its length, see [draw_bezier_cubic_segments].
+pub fn draw_bezier_cubic(p0: Vec2, p1: Vec2, p2: Vec2, p3: Vec2, thickness: f32, color: Color) {
+    let segments = curve_segments(&[p0, p1, p2, p3]);
+    draw_bezier_cubic_segments(p0, p1, p2, p3, segments, thickness, color);
+}
+
+/// Same as [draw_bezier_cubic] with the curve split into `segments` straight segments.
+pub fn draw_bezier_cubic_segments(p0: Vec2, p1: Vec2, p2: Vec2, p3: Vec2, segments: usize, thickness: f32, color: Color) {
+    let segments = segments.max(1);
+    let points: Vec<Vec2> = (0..=segments)
+        .map(|i| {
+            let t = i as f32 / segments as f32;
+            let u = 1. - t;
+            u * u * u * p0 + 3. * u * u * t * p1 + 3. * u * t * t * p2 + t * t * t * p3
+        })
+        .collect();
+
+    draw_polyline(&points, false, thickness, color);
+}
+
+/// Segments for a Bézier curve, about one every 4 units along its control polygon,
+/// which is never shorter than the curve.
+fn curve_segments(control_points: &[Vec2]) -> usize {
+    let length: f32 = control_points.windows(2).map(|pair| pair[0].distance(pair[1])).sum();
+    (length / 4.).ceil().clamp(4., 256.) as usize
+}
+
+/// Draws connected lines through `points` with mitered joins, so there are no gaps at the bends,
+/// going back to the first point if `closed`.
+/// The miters are cut short at sharp bends to not spike far out of the line.
+fn draw_polyline(points: &[Vec2], closed: bool, thickness: f32, color: Color) {
+    const MITER_LIMIT: f32 = 4.;
+
+    let mut path = Vec::<Vec2>::with_capacity(points.len());
+    for point in points {
+        match path.last() {
+            Some(last) if last.distance(*point) <= f32::EPSILON => {}
+            _ => path.push(*point),
+        }
+    }
+    if closed && path.len() > 2 && path[0].distance(path[path.len() - 1]) <= f32::EPSILON {
+        path.pop();
+    }
+    if path.len() < 2 {
+        return;
+    }
+
+    let context = get_context();
+    let half = thickness / 2.;
+    let z = context.gl.draw_depth();
+    let normal = |from: Vec2, to: Vec2| (to - from).normalize().perp();
+
+    let count = path.len();
+    let mut vertices = Vec::with_capacity(count * 2);
+    for i in 0..count {
+        let previous = if i > 0 { Some(i - 1) } else { closed.then_some(count - 1) };
+        let next = if i + 1 < count { Some(i + 1) } else { closed.then_some(0) };
+        let before = previous.map(|previous| normal(path[previous], path[i]));
+        let after = next.map(|next| normal(path[i], path[next]));
+
+        let offset = match (before, after) {
+            (Some(before), Some(after)) => {
+                let miter = (before + after).normalize_or_zero();
+                let cos = miter.dot(before);
+                if cos > f32::EPSILON {
+                    miter * (half / cos).min(half * MITER_LIMIT)
+                } else {
+                    // the line turns right back
+                    before * half
+                }
+            }
+            (Some(normal), None) | (None, Some(normal)) => normal * half,
+            (None, None) => unreachable!(),
+        };
+
+        let point = path[i];
+        vertices.push(Vertex::new(point.x + offset.x, point.y + offset.y, z, 0., 0., color));
+        vertices.push(Vertex::new(point.x - offset.x, point.y - offset.y, z, 0., 0., color));
+    }
+
+    let segments = if closed { count } else { count - 1 };
+    let mut indices = Vec::with_capacity(segments * 6);
+    for i in 0..segments {
+        let start = i as u16 * 2;
+        let end = ((i + 1) % count) as u16 * 2;
+        indices.extend_from_slice(&[start, start + 1, end, end, start + 1, end + 1]);
+    }
+
+    context.gl.texture(None);
+    context.gl.draw_mode(DrawMode::Triangles);
+    context.gl.geometry(&vertices, &indices);
+}
+
+/// Draws a solid polygon through `points`, which may be concave but should not cross itself, with a given `color`.
+///
+/// The polygon is split into triangles by ear clipping, nothing is drawn for less than 3 points
+/// or when all of them are on a line.
+pub fn draw_polygon(points: &[Vec2], color: Color) {
+    let indices = triangulate_polygon(points);
+    if indices.is_empty() {
+        return;
+    }
+
+    let context = get_context();
+    let z = context.gl.draw_depth();
+    let vertices: Vec<Vertex> = points.iter().map(|p| Vertex::new(p.x, p.y, z, 0., 0., color)).collect();
+
     context.gl.texture(None);
     context.gl.draw_mode(DrawMode::Triangles);
-    context.gl.geometry(
-        &[
-            Vertex::new(x1 + tx, y1 + ty, 0., 0., 0., color),
-            Vertex::new(x1 - tx, y1 - ty, 0., 0., 0., color),
-            Vertex::new(x2 + tx, y2 + ty, 0., 0., 0., color),
-            Vertex::new(x2 - tx, y2 - ty, 0., 0., 0., color),
-        ],
-        &[0, 1, 2, 2, 1, 3],
-    );
+    context.gl.geometry(&vertices, &indices);
+}
+
+/// Draws the outline of the polygon through `points`, going back to the first one, with a given
+/// line `thickness` centered on the edges and `color`.
+pub fn draw_polygon_lines(points: &[Vec2], thickness: f32, color: Color) {
+    draw_polyline(points, true, thickness, color);
+}
+
+/// Indices of the triangles filling the polygon through `points`, three by three, by ear clipping.
+/// Empty for less than 3 points or no area.
+fn triangulate_polygon(points: &[Vec2]) -> Vec<u16> {
+    // twice the signed area, positive when going counter clockwise in a y up space
+    let doubled_area: f32 = (0..points.len()).map(|i| points[i].perp_dot(points[(i + 1) % points.len()])).sum();
+    if points.len() < 3 || points.len() > u16::MAX as usize || doubled_area.abs() <= f32::EPSILON {
+        return vec![];
+    }
+
+    // the corners left, going counter clockwise
+    let mut remaining: Vec<u16> = (0..points.len() as u16).collect();
+    if doubled_area < 0. {
+        remaining.reverse();
+    }
+
+    let mut indices = Vec::with_capacity((points.len() - 2) * 3);
+    let mut i = 0;
+    // corners checked in a row without finding an ear, a whole round means the polygon crosses itself
+    let mut checked = 0;
+    while remaining.len() > 2 && checked < remaining.len() {
+        let count = remaining.len();
+        let [a, b, c] = [(i + count - 1) % count, i, (i + 1) % count].map(|k| remaining[k]);
+        let [pa, pb, pc] = [a, b, c].map(|k| points[k as usize]);
+
+        let turn = (pb - pa).perp_dot(pc - pb);
+        let inside = |k: &u16| ![a, b, c].contains(k) && in_triangle(points[*k as usize], pa, pb, pc);
+        let overlaps = || remaining.iter().any(inside);
+        if turn.abs() <= f32::EPSILON {
+            // on a line with its neighbours, adds nothing
+            remaining.remove(i);
+            checked = 0;
+        } else if turn > 0. && !overlaps() {
+            indices.extend_from_slice(&[a, b, c]);
+            remaining.remove(i);
+            checked = 0;
+        } else {
+            i += 1;
+            checked += 1;
+        }
+        if !remaining.is_empty() {
+            i %= remaining.len();
+        }
+    }
+    indices
+}
+
+/// Whether `p` is inside of or on the edges of the counter clockwise triangle `a`, `b`, `c`.
+fn in_triangle(p: Vec2, a: Vec2, b: Vec2, c: Vec2) -> bool {
+    (b - a).perp_dot(p - a) >= 0. && (c - b).perp_dot(p - b) >= 0. && (a - c).perp_dot(p - c) >= 0.
+}
+
+#[test]
+fn polygon_triangulation() {
+    let area = |points: &[Vec2], indices: &[u16]| -> f32 {
+        let triangle_area = |t: &[u16]| {
+            let [a, b, c] = [t[0], t[1], t[2]].map(|k| points[k as usize]);
+            (b - a).perp_dot(c - a).abs() / 2.
+        };
+        indices.chunks(3).map(triangle_area).sum()
+    };
+
+    // concave arrow, both windings
+    let mut arrow = vec![vec2(0., 0.), vec2(4., 2.), vec2(0., 4.), vec2(1., 2.)];
+    assert_eq!(triangulate_polygon(&arrow).len(), 6);
+    assert!((area(&arrow, &triangulate_polygon(&arrow)) - 6.).abs() < 1e-4);
+    arrow.reverse();
+    assert!((area(&arrow, &triangulate_polygon(&arrow)) - 6.).abs() < 1e-4);
+
+    // L shape with a corner in the middle of an edge
+    let l_shape = [(0., 0.), (2., 0.), (2., 1.), (1., 1.), (1., 3.), (0., 3.), (0., 1.5)].map(Vec2::from);
+    assert!((area(&l_shape, &triangulate_polygon(&l_shape)) - 4.).abs() < 1e-4);
+
+    assert!(triangulate_polygon(&[]).is_empty());
+    assert!(triangulate_polygon(&[vec2(0., 0.), vec2(1., 1.)]).is_empty());
+    assert!(triangulate_polygon(&[vec2(0., 0.), vec2(1., 1.), vec2(2., 2.), vec2(3., 3.)]).is_empty());
+}
+
+/// Draws each point as a square of `size` (in pixels with the default camera) centered on it,
+/// all in a few batched draw calls.
+pub fn draw_points(points: &[Vec2], size: f32, color: Color) {
+    draw_point_quads(points.iter().map(|point| (*point, color)), size);
+}
+
+/// Same as [draw_points], with a color per point.
+pub fn draw_points_colored(points: &[(Vec2, Color)], size: f32) {
+    draw_point_quads(points.iter().copied(), size);
+}
+
+fn draw_point_quads(points: impl Iterator<Item = (Vec2, Color)>, size: f32) {
+    // Keeps each chunk well below the default draw call capacity (10000 vertices, 5000 indices).
+    const POINTS_PER_CHUNK: usize = 512;
+
+    let context = get_context();
+    let z = context.gl.draw_depth();
+    let half = size / 2.;
+
+    context.gl.texture(None);
+    context.gl.draw_mode(DrawMode::Triangles);
+
+    let mut vertices = Vec::<Vertex>::with_capacity(POINTS_PER_CHUNK * 4);
+    let mut indices = Vec::<u16>::with_capacity(POINTS_PER_CHUNK * 6);
+
+    for (point, color) in points {
+        indices.extend([0, 1, 2, 0, 2, 3].map(|k| k + vertices.len() as u16));
+        vertices.extend([
+            Vertex::new(point.x - half, point.y - half, z, 0., 0., color),
+            Vertex::new(point.x + half, point.y - half, z, 0., 0., color),
+            Vertex::new(point.x + half, point.y + half, z, 0., 0., color),
+            Vertex::new(point.x - half, point.y + half, z, 0., 0., color),
+        ]);
+
+        if vertices.len() >= POINTS_PER_CHUNK * 4 {
+            context.gl.geometry(&vertices, &indices);
+            vertices.clear();
+            indices.clear();
+        }
+    }
+
+    if !vertices.is_empty() {
+        context.gl.geometry(&vertices, &indices);
+    }
+}
+
+/// Draws a grid of lines covering `area`, with one line every `cell` units on each axis,
+/// a given line `thickness` and `color`.
+///
+/// The area borders are always included. The whole grid is drawn with a single `draw_lines` batch.
+pub fn draw_grid_2d(area: Rect, cell: Vec2, thickness: f32, color: Color) {
+    if cell.x <= 0. || cell.y <= 0. {
+        return;
+    }
+
+    let columns = (area.w / cell.x).floor() as usize;
+    let rows = (area.h / cell.y).floor() as usize;
+
+    let mut lines = Vec::with_capacity(columns + rows + 4);
+    for i in 0..=columns {
+        let x = area.x + i as f32 * cell.x;
+        lines.push((vec2(x, area.top()), vec2(x, area.bottom())));
+    }
+    if area.x + columns as f32 * cell.x < area.right() {
+        lines.push((vec2(area.right(), area.top()), vec2(area.right(), area.bottom())));
+    }
+    for i in 0..=rows {
+        let y = area.y + i as f32 * cell.y;
+        lines.push((vec2(area.left(), y), vec2(area.right(), y)));
+    }
+    if area.y + rows as f32 * cell.y < area.bottom() {
+        lines.push((vec2(area.left(), area.bottom()), vec2(area.right(), area.bottom())));
+    }
+
+    draw_lines(&lines, thickness, color);
 }
 
 /// Draw arc from `rotation`(in degrees) to `arc + rotation` (`arc` in degrees),
@@ -375,3 +884,104 @@ pub fn draw_arc(x: f32, y: f32, sides: u8, radius: f32, rotation: f32, thickness
 
     context.gl.geometry(&verticies, &indicies);
 }
+
+/// Draws a stroked arc centered at `[x, y]` from `start_angle` to `end_angle` (in radians, clockwise
+/// with the default camera), with a given line `thickness` going out from `radius`, and `color`.
+///
+/// Unlike [draw_arc], the number of segments follows the radius and the span, and `end_angle` may be
+/// below `start_angle` to go the other way around, or more than a turn past it to wrap around `2π`.
+pub fn draw_arc_between(x: f32, y: f32, radius: f32, start_angle: f32, end_angle: f32, thickness: f32, color: Color) {
+    let context = get_context();
+
+    let center = vec2(x, y);
+    let radius = radius.max(0.);
+    let thickness = thickness.max(0.);
+    let points = arc_points(radius + thickness / 2., start_angle, end_angle);
+    let z = context.gl.draw_depth();
+
+    let mut vertices = Vec::with_capacity(points.len() * 2);
+    let mut indices = Vec::with_capacity(points.len() * 6);
+    for (i, direction) in points.iter().enumerate() {
+        let inner = center + *direction * radius;
+        let outer = center + *direction * (radius + thickness);
+        vertices.push(Vertex::new(inner.x, inner.y, z, 0., 0., color));
+        vertices.push(Vertex::new(outer.x, outer.y, z, 0., 0., color));
+
+        if i > 0 {
+            indices.extend([0, 1, 2, 2, 1, 3].map(|k| k + (i as u16 - 1) * 2));
+        }
+    }
+
+    context.gl.texture(None);
+    context.gl.draw_mode(DrawMode::Triangles);
+    context.gl.geometry(&vertices, &indices);
+}
+
+/// Draws a solid pie slice centered at `[x, y]` with a given `radius`, from `start_angle` to `end_angle`
+/// (in radians, clockwise with the default camera), and `color`, e.g. for pie charts and cooldowns.
+///
+/// The angles work as in [draw_arc_between].
+pub fn draw_sector(x: f32, y: f32, radius: f32, start_angle: f32, end_angle: f32, color: Color) {
+    let context = get_context();
+
+    let radius = radius.max(0.);
+    let points = arc_points(radius, start_angle, end_angle);
+    let z = context.gl.draw_depth();
+
+    let mut vertices = Vec::with_capacity(points.len() + 1);
+    let mut indices = Vec::with_capacity(points.len() * 3);
+    vertices.push(Vertex::new(x, y, z, 0., 0., color));
+    for (i, direction) in points.iter().enumerate() {
+        let point = vec2(x, y) + *direction * radius;
+        vertices.push(Vertex::new(point.x, point.y, z, direction.x, direction.y, color));
+
+        if i > 0 {
+            indices.extend_from_slice(&[0, i as u16, i as u16 + 1]);
+        }
+    }
+
+    context.gl.texture(None);
+    context.gl.draw_mode(DrawMode::Triangles);
+    context.gl.geometry(&vertices, &indices);
+}
+
+/// Unit directions from `start_angle` to `end_angle`, at most a full turn, split into segments of about
+/// 4 units along a circle of `radius`.
+fn arc_points(radius: f32, start_angle: f32, end_angle: f32) -> Vec<Vec2> {
+    let span = (end_angle - start_angle).clamp(-std::f32::consts::TAU, std::f32::consts::TAU);
+    let segments = (radius * span.abs() / 4.).ceil().clamp(1., 128.) as usize;
+
+    (0..=segments)
+        .map(|i| Vec2::from_angle(start_angle + span * i as f32 / segments as f32))
+        .collect()
+}
+
+/// Outline of `rect` with its corners rounded by `radius`, clamped to half its smaller side,
+/// going clockwise from the left end of the top left corner with `segments` steps per corner.
+///
+/// Shared by the rounded shapes, growing `rect` and `radius` by the same amount
+/// gives an outline with the same number of points, each moved along its normal.
+pub(crate) fn rounded_rect_points(rect: Rect, radius: f32, segments: usize) -> Vec<Vec2> {
+    rounded_rect_corner_points(rect, [radius; 4], segments)
+}
+
+/// Same as [rounded_rect_points] with a radius per corner, in the order the corners are gone through.
+fn rounded_rect_corner_points(rect: Rect, radii: [f32; 4], segments: usize) -> Vec<Vec2> {
+    let radii = radii.map(|radius| radius.clamp(0., rect.w.min(rect.h) / 2.));
+    let centers = [
+        vec2(rect.left() + radii[0], rect.top() + radii[0]),
+        vec2(rect.right() - radii[1], rect.top() + radii[1]),
+        vec2(rect.right() - radii[2], rect.bottom() - radii[2]),
+        vec2(rect.left() + radii[3], rect.bottom() - radii[3]),
+    ];
+
+    let mut points = Vec::with_capacity(4 * (segments + 1));
+    for (corner, (center, radius)) in centers.into_iter().zip(radii).enumerate() {
+        let start = std::f32::consts::PI + corner as f32 * std::f32::consts::FRAC_PI_2;
+        for i in 0..=segments {
+            let angle = start + i as f32 / segments as f32 * std::f32::consts::FRAC_PI_2;
+            points.push(center + radius * Vec2::from_angle(angle));
+        }
+    }
+    points
+}
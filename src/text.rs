@@ -1,5 +1,6 @@
 //! Functions to load fonts and draw text.
 
+use std::collections::HashMap;
 use std::sync::OnceLock;
 
 use crate::{
@@ -10,7 +11,7 @@ use crate::{
 };
 
 use crate::color::WHITE;
-use glam::vec2;
+use glam::{vec2, Vec2};
 use serde::Deserialize;
 
 use crate::texture::Texture2D;
@@ -33,6 +34,14 @@ pub struct Font {
     character_regions: Vec<QuadFontCharacterInfo>,
     // NEW: maps extended ASCII byte (0..255) -> index into character_regions, or u16::MAX if missing.
     index_map: [u16; 256],
+    /// Scale the atlas was baked at relative to `font_size`, glyph metrics are divided by it when laying out text.
+    dpi_scale: f32,
+    /// Advance adjustments between pairs of consecutive glyphs, in pixels of the atlas.
+    kerning: HashMap<(char, char), f32>,
+    /// Index into character_regions of the glyphs beyond extended ASCII, only bitmap fonts have them.
+    extra_glyphs: HashMap<char, u16>,
+    /// Fonts consulted in order for the glyphs this font lacks, see [Font::add_fallback].
+    fallbacks: Vec<Font>,
 }
 
 const DEFAULT_FONT_ATLAS: &[u8] = include_bytes!("../DefaultFont_atlas.png");
@@ -118,9 +127,40 @@ impl Font {
             atlas,
             character_regions,
             index_map,
+            dpi_scale: 1.0,
+            kerning: HashMap::new(),
+            extra_glyphs: HashMap::new(),
+            fallbacks: vec![],
         })
     }
 
+    /// Swaps the glyph atlas for one baked at `font_size * scale`, keeping the text size on screen.
+    ///
+    /// Prebaked atlases can't be rasterized again at runtime, so the caller provides the atlas
+    /// for the new scale, e.g. picked from a few baked sizes after [crate::window::screen_dpi_changed].
+    /// Text drawn with an atlas baked at [crate::window::screen_dpi_scale] maps glyphs one to one to pixels.
+    ///
+    /// `character_regions` are the same glyphs in the same order as the current ones, so the glyph map,
+    /// including characters above U+00FF, the kerning and the fallbacks are kept.
+    /// A different glyph count is an error and leaves the font unchanged.
+    pub fn rebuild_for_dpi(&mut self, scale: f32, atlas: Texture2D, character_regions: Vec<QuadFontCharacterInfo>) -> Result<(), Error> {
+        assert!(scale > 0., "dpi scale must be positive");
+
+        if character_regions.len() != self.character_regions.len() {
+            return Err(Error::FontError("rebuilt font atlas has a different glyph count"));
+        }
+
+        self.atlas = atlas;
+        self.character_regions = character_regions;
+        self.dpi_scale = scale;
+        Ok(())
+    }
+
+    /// Scale the glyph atlas was baked at, 1 unless changed with [Font::rebuild_for_dpi].
+    pub fn dpi_scale(&self) -> f32 {
+        self.dpi_scale
+    }
+
     // REMOVE old char_index/region_index logic; font size is fixed and we have exactly extended ASCII mapping.
     // New: map Unicode char to extended ASCII byte (0..255). Anything >255 becomes '?' (63).
     #[inline(always)]
@@ -135,23 +175,137 @@ impl Font {
 
     #[inline(always)]
     fn get_info(&self, c: char) -> &QuadFontCharacterInfo {
-        let code = Self::extended_ascii_index(c);
-        let idx = self.index_map[code] as usize;
-        if idx != u16::MAX as usize && idx < self.character_regions.len() {
-            // Safety: idx is validated against len
-            unsafe { &self.character_regions.get_unchecked(idx) }
+        self.glyph(c).1
+    }
+
+    /// Glyph of `c` in this font itself, without the fallbacks.
+    #[inline(always)]
+    fn own_glyph(&self, c: char) -> Option<&QuadFontCharacterInfo> {
+        let idx = match c as u32 {
+            code @ 0..=255 => self.index_map[code as usize],
+            _ => *self.extra_glyphs.get(&c)?,
+        };
+        // u16::MAX, the missing glyph marker, is past the end of character_regions
+        self.character_regions.get(idx as usize)
+    }
+
+    /// Font drawing `c` and its glyph: this font, else the first fallback having the glyph.
+    fn find_glyph(&self, c: char) -> Option<(&Font, &QuadFontCharacterInfo)> {
+        match self.own_glyph(c) {
+            Some(info) => Some((self, info)),
+            None => self.fallbacks.iter().find_map(|fallback| fallback.find_glyph(c)),
+        }
+    }
+
+    /// Same as `find_glyph`, characters missing from all the fonts get a placeholder glyph of this font.
+    fn glyph(&self, c: char) -> (&Font, &QuadFontCharacterInfo) {
+        if let Some(glyph) = self.find_glyph(c) {
+            return glyph;
+        }
+
+        let placeholder = char::from(Self::extended_ascii_index(c) as u8);
+        // Fonts without ':' (e.g. partial bitmap fonts) fall back to their first glyph
+        let info = self
+            .own_glyph(placeholder)
+            .or_else(|| self.own_glyph(':'))
+            .unwrap_or(&self.character_regions[0]);
+        (self, info)
+    }
+
+    /// Adds a font drawing the characters missing from this one, e.g. a bitmap font with CJK glyphs.
+    /// Fallbacks are consulted in the order they were added, along with their own fallbacks.
+    ///
+    /// Fallbacks should be baked at the same size and dpi scale: their glyph metrics are used as is.
+    /// Every switch between glyphs of different atlases starts a new draw call,
+    /// so text mixing fonts on every other glyph batches poorly.
+    pub fn add_fallback(&mut self, other: Font) {
+        self.fallbacks.push(other);
+    }
+
+    /// Horizontal advance of `c` in pixels at `font_scale` 1, as used by `measure_text` and `draw_text_ex`.
+    ///
+    /// Characters missing from the font get the same fallback glyph as when drawing.
+    pub fn char_advance(&self, c: char) -> f32 {
+        self.get_info(c).advance
+    }
+
+    /// Glyph metrics and atlas region of `c`, with the same fallback as [Font::char_advance].
+    pub fn char_info(&self, c: char) -> QuadFontCharacterInfo {
+        self.get_info(c).clone()
+    }
+
+    /// Kerning adjustment between `first` and `second` in pixels at `font_scale` 1, 0 for pairs without one.
+    pub fn kerning(&self, first: char, second: char) -> f32 {
+        self.kerning.get(&(first, second)).copied().unwrap_or(0.)
+    }
+
+    /// Sets the kerning adjustment between `first` and `second`, e.g. for atlases baked without kerning data.
+    pub fn set_kerning(&mut self, first: char, second: char, amount: f32) {
+        if amount == 0. {
+            self.kerning.remove(&(first, second));
         } else {
-            self.get_info(':')
+            self.kerning.insert((first, second), amount);
+        }
+    }
+
+    /// Kerning between `c`, drawn from `chars[i]`, and the next drawn glyph, markup tags in between are skipped.
+    fn kerning_after(&self, chars: &[char], i: usize, c: char) -> f32 {
+        if self.kerning.is_empty() {
+            return 0.;
+        }
+
+        let mut j = i + 1;
+        while j < chars.len() {
+            let next = match chars[j] {
+                '[' => match parse_markup(chars, j) {
+                    (MarkupResult::Literal(literal), _) => literal,
+                    (MarkupResult::Noop, _) => '[',
+                    (_, next_pos) => {
+                        j = next_pos;
+                        continue;
+                    }
+                },
+                next => next,
+            };
+            return self.kerning(c, next);
         }
+        0.
+    }
+
+    /// Height of the tallest glyph of `chars` at the scale of its size tags for a font scale of 1,
+    /// the chars of the markup tags count as unscaled text. Lines are that far apart.
+    fn tallest_glyph(&self, chars: &[char]) -> f32 {
+        let mut tallest: f32 = 0.;
+        let mut scale = 1.;
+        let mut scales = vec![];
+
+        let mut i = 0;
+        while i < chars.len() {
+            if chars[i] == '[' {
+                let (action, next_pos) = parse_markup(chars, i);
+                if !matches!(action, MarkupResult::Literal(_) | MarkupResult::Noop) {
+                    for tag_char in &chars[i..next_pos] {
+                        tallest = tallest.max(self.get_info(*tag_char).region.h);
+                    }
+                    apply_scale_markup(&action, &mut scale, &mut scales);
+                    i = next_pos;
+                    continue;
+                }
+            }
+            tallest = tallest.max(self.get_info(chars[i]).region.h * scale);
+            i += 1;
+        }
+        tallest
     }
 
     pub(crate) fn measure_text(
         &self,
         text: impl AsRef<str>,
         font_size_unscaled: u16,
-        font_scale_x: f32,
-        font_scale_y: f32,
+        font_scale: Vec2,
         max_line_width_unscaled: Option<f32>,
+        kerning: bool,
+        mut breaks: Option<&mut Vec<usize>>,
     ) -> TextDimensions {
         unsafe {
             let text = text.as_ref();
@@ -160,25 +314,27 @@ impl Font {
                 return TextDimensions::default();
             }
 
-            let dpi_scaling = 1.0; //miniquad::window::dpi_scale();
-            let max_line_width_pixels = max_line_width_unscaled.map(|w| w * dpi_scaling);
+            // glyph metrics are in pixels of the atlas, baked at `font_size * dpi_scale`,
+            // folding the dpi scale into the font scale lays the text out in logical pixels
+            let dpi_scaling = self.dpi_scale;
+            let base_font_scale = font_scale / dpi_scaling;
+            let mut font_scale_x = base_font_scale.x;
+            let mut font_scale_y = base_font_scale.y;
+            let max_line_width_pixels = max_line_width_unscaled;
 
-            let unique_characters_from_text: std::collections::HashSet<char> = text.chars().collect();
+            let chars: Vec<char> = text.chars().collect();
 
             let new_line_padding = 2.0;
             let mut layout_line_height_scaled: f32 = 0.0;
             if !text.is_empty() {
-                for character in unique_characters_from_text.iter() {
-                    let info = &self.get_info(*character).region;
-                    layout_line_height_scaled = layout_line_height_scaled.max(info.h * font_scale_y);
-                }
+                layout_line_height_scaled = self.tallest_glyph(&chars) * font_scale_y;
                 if layout_line_height_scaled == 0.0 {
                     layout_line_height_scaled = font_size_unscaled as f32 * font_scale_y * dpi_scaling;
                 }
                 if layout_line_height_scaled <= 0.0 {
-                    layout_line_height_scaled = font_size_unscaled as f32 * dpi_scaling;
+                    layout_line_height_scaled = font_size_unscaled as f32;
                     if layout_line_height_scaled <= 0.0 {
-                        layout_line_height_scaled = 1.0 * dpi_scaling;
+                        layout_line_height_scaled = 1.0;
                     }
                 }
                 layout_line_height_scaled += new_line_padding;
@@ -186,7 +342,7 @@ impl Font {
             let unscaled_layout_line_h = if layout_line_height_scaled <= 0.0 {
                 0.0
             } else {
-                layout_line_height_scaled / dpi_scaling
+                layout_line_height_scaled
             };
 
             let mut current_line_scaled_width: f32 = 0.0;
@@ -200,9 +356,19 @@ impl Font {
             // Track characters added to current line for trimming trailing whitespace
             let mut current_line_chars = Vec::<(char, f32)>::with_capacity(64);
 
-            let chars: Vec<char> = text.chars().collect();
+            // scale of the size tags, see `apply_scale_markup`
+            let mut markup_scale = 1.;
+            let mut markup_scales = vec![];
+
             let length = chars.len();
             let mut i = 0;
+            // index of the current char among the chars of `text`, markup tags excluded
+            let mut char_index = 0;
+            let mut record_break = |index: usize| {
+                if let Some(breaks) = breaks.as_deref_mut() {
+                    breaks.push(index);
+                }
+            };
 
             // Helper function to trim trailing whitespace from current line
             // Returns the untrimmed width (with spaces included)
@@ -230,7 +396,11 @@ impl Font {
                         MarkupResult::Literal(char_literal) => {
                             c = char_literal; // continue with this literal char
                         }
-                        MarkupResult::Push(_) | MarkupResult::Pop => {
+                        MarkupResult::Push(_)
+                        | MarkupResult::Pop
+                        | MarkupResult::Decorate(_)
+                        | MarkupResult::Undecorate(_)
+                        | MarkupResult::Scale(_) => {
                             // Flush current word buffer to the line, like draw_text_ex does
                             for (_c2, adv) in word_buffer.drain(..) {
                                 let info = self.get_info(_c2);
@@ -242,6 +412,10 @@ impl Font {
                             }
                             current_word_width_scaled = 0.0;
 
+                            apply_scale_markup(&action, &mut markup_scale, &mut markup_scales);
+                            font_scale_x = base_font_scale.x * markup_scale;
+                            font_scale_y = base_font_scale.y * markup_scale;
+
                             // Do not add a line here; just switch styles. Skip tag text.
                             i = next_pos;
                             continue;
@@ -249,6 +423,9 @@ impl Font {
                     }
                 }
 
+                let index = char_index;
+                char_index += 1;
+
                 if c == '\n' {
                     // Flush buffered word and push a line ALWAYS (even if empty), matching draw_text_ex
                     for (_c2, adv) in word_buffer.drain(..) {
@@ -262,7 +439,7 @@ impl Font {
                     current_word_width_scaled = 0.0;
 
                     max_line_width_used_scaled = max_line_width_used_scaled.max(current_line_scaled_width);
-                    measured_lines_unscaled.push(glam::vec2(current_line_scaled_width / dpi_scaling, unscaled_layout_line_h));
+                    measured_lines_unscaled.push(glam::vec2(current_line_scaled_width, unscaled_layout_line_h));
 
                     current_line_scaled_width = 0.0;
                     current_line_chars.clear();
@@ -271,7 +448,8 @@ impl Font {
                 }
 
                 let info = self.get_info(c);
-                let advance_scaled = info.advance * font_scale_x;
+                let kerning_adjustment = if kerning { self.kerning_after(&chars, i, c) } else { 0. };
+                let advance_scaled = (info.advance + kerning_adjustment) * font_scale_x;
                 let char_offset_y_s = info.offset_y as f32 * font_scale_y;
                 let char_visual_max_y_s = info.region.h * font_scale_y + char_offset_y_s;
                 overall_max_y_offset_scaled = overall_max_y_offset_scaled.max(char_visual_max_y_s);
@@ -295,15 +473,17 @@ impl Font {
                             trim_trailing_whitespace(&mut current_line_scaled_width, &mut current_line_chars);
 
                             max_line_width_used_scaled = max_line_width_used_scaled.max(current_line_scaled_width);
-                            measured_lines_unscaled.push(glam::vec2(current_line_scaled_width / dpi_scaling, unscaled_layout_line_h));
+                            measured_lines_unscaled.push(glam::vec2(current_line_scaled_width, unscaled_layout_line_h));
                             current_line_scaled_width = 0.0;
                             current_line_chars.clear();
 
                             // Skip leading space/tab on new line, but NOT '-'
                             if c == ' ' || c == '\t' {
+                                record_break(index + 1);
                                 i += 1;
                                 continue;
                             }
+                            record_break(index);
                         }
                     }
 
@@ -320,11 +500,12 @@ impl Font {
                                 let untrimmed = trim_trailing_whitespace(&mut current_line_scaled_width, &mut current_line_chars);
 
                                 max_line_width_used_scaled = max_line_width_used_scaled.max(untrimmed);
-                                measured_lines_unscaled.push(glam::vec2(untrimmed / dpi_scaling, unscaled_layout_line_h));
+                                measured_lines_unscaled.push(glam::vec2(untrimmed, unscaled_layout_line_h));
 
                                 // Reset line width and flush word buffer to new line
                                 current_line_scaled_width = 0.0;
                                 current_line_chars.clear();
+                                record_break(index - word_buffer.len());
                                 for (_wc, w_adv) in word_buffer.drain(..) {
                                     let info = self.get_info(_wc);
                                     let char_offset_y_s = info.offset_y as f32 * font_scale_y;
@@ -336,13 +517,15 @@ impl Font {
                                 current_word_width_scaled = 0.0;
                             } else {
                                 // Word is too long for empty line, break it character by character
-                                for (_wc, w_adv) in word_buffer.drain(..) {
+                                let word_start = index - word_buffer.len();
+                                for (k, (_wc, w_adv)) in word_buffer.drain(..).enumerate() {
                                     if current_line_scaled_width + w_adv > max_w_pixels && current_line_scaled_width > 0.0 {
+                                        record_break(word_start + k);
                                         // Use untrimmed width for max tracking
                                         let untrimmed = trim_trailing_whitespace(&mut current_line_scaled_width, &mut current_line_chars);
 
                                         max_line_width_used_scaled = max_line_width_used_scaled.max(untrimmed);
-                                        measured_lines_unscaled.push(glam::vec2(untrimmed / dpi_scaling, unscaled_layout_line_h));
+                                        measured_lines_unscaled.push(glam::vec2(untrimmed, unscaled_layout_line_h));
                                         current_line_scaled_width = 0.0;
                                         current_line_chars.clear();
                                     }
@@ -356,11 +539,12 @@ impl Font {
                                 current_word_width_scaled = 0.0;
 
                                 if current_line_scaled_width + advance_scaled > max_w_pixels && current_line_scaled_width > 0.0 {
+                                    record_break(index);
                                     // Use untrimmed width for max tracking
                                     let untrimmed = trim_trailing_whitespace(&mut current_line_scaled_width, &mut current_line_chars);
 
                                     max_line_width_used_scaled = max_line_width_used_scaled.max(untrimmed);
-                                    measured_lines_unscaled.push(glam::vec2(untrimmed / dpi_scaling, unscaled_layout_line_h));
+                                    measured_lines_unscaled.push(glam::vec2(untrimmed, unscaled_layout_line_h));
                                     current_line_scaled_width = 0.0;
                                     current_line_chars.clear();
                                 }
@@ -390,25 +574,25 @@ impl Font {
             // Don't trim trailing whitespace from the final line - we want the actual width with spaces
             max_line_width_used_scaled = max_line_width_used_scaled.max(current_line_scaled_width);
             if current_line_scaled_width > 0.0 || (measured_lines_unscaled.is_empty() && !text.is_empty()) {
-                measured_lines_unscaled.push(glam::vec2(current_line_scaled_width / dpi_scaling, unscaled_layout_line_h));
+                measured_lines_unscaled.push(glam::vec2(current_line_scaled_width, unscaled_layout_line_h));
             }
 
             let final_width_unscaled = if measured_lines_unscaled.is_empty() {
                 0.0
             } else {
-                max_line_width_used_scaled / dpi_scaling
+                max_line_width_used_scaled
             };
 
             let calculated_total_height_unscaled = if measured_lines_unscaled.is_empty() {
                 0.0
             } else {
-                (measured_lines_unscaled.len() as f32 * layout_line_height_scaled) / dpi_scaling
+                measured_lines_unscaled.len() as f32 * layout_line_height_scaled
             };
 
             let final_offset_y_unscaled = if overall_max_y_offset_scaled == f32::MIN {
                 0.0
             } else {
-                overall_max_y_offset_scaled / dpi_scaling
+                overall_max_y_offset_scaled
             };
 
             TextDimensions {
@@ -422,7 +606,7 @@ impl Font {
 }
 
 /// Arguments for "draw_text_ex" function such as font, font_size etc
-#[derive(Debug, Clone)]
+#[derive(Clone)]
 pub struct TextParams<'a> {
     pub font: Option<&'a Font>,
     /// The glyphs sizes actually drawn on the screen will be font_size * font_scale
@@ -436,13 +620,60 @@ pub struct TextParams<'a> {
     /// Default is 0.0
     pub rotation: f32,
     pub color: Color,
-    /// Enable text markup with [#RRGGBB] or [#RRGGBBAA] color tags
+    /// Enable text markup with [#RRGGBB] or [#RRGGBBAA] color tags closed by [],
+    /// [u] underline tags closed by [/u] and [s] strikethrough tags closed by [/s].
+    /// [size=1.5] tags closed by [] multiply font_scale and apply even with markup disabled,
+    /// as `measure_text` lays them out too
     /// Default is true
     pub enable_markup: bool,
     /// Maximum width of a line in pixels, text will wrap if it exceeds this width
     /// None means no wrapping
     /// Default is None
     pub max_line_width: Option<f32>,
+    /// Instead of wrapping at `max_line_width`, cut the text there and end it with an ellipsis,
+    /// '…' when the font has it or "...". Ignored for text with '\n'.
+    /// Use `measure_text_ex` to measure the truncated text.
+    /// Default is false
+    pub truncate_ellipsis: bool,
+    /// Computes the color of each glyph from its index among the drawn glyphs (markup tags excluded)
+    /// and its character, overriding `color` and markup colors. Handy for rainbow or shimmer effects.
+    /// Default is None
+    pub color_fn: Option<&'a dyn Fn(usize, char) -> Color>,
+    /// Computes an offset added to the position of each glyph from its index among the drawn glyphs
+    /// and its character, e.g. for wavy text. Does not affect layout or `measure_text`.
+    /// Default is None
+    pub offset_fn: Option<&'a dyn Fn(usize, char) -> Vec2>,
+    /// Apply the kerning pairs of the font between consecutive glyphs, fonts without kerning data are unaffected
+    /// Default is true
+    pub kerning: bool,
+    /// Draw the text once more behind itself, moved by the offset and in the given color, ignoring markup colors.
+    /// Does not affect layout or `measure_text`.
+    /// Default is None
+    pub shadow: Option<(Vec2, Color)>,
+    /// Outline the glyphs with the given thickness and color by drawing the text 8 more times around itself,
+    /// ignoring markup colors. Does not affect layout or `measure_text`.
+    /// Default is None
+    pub outline: Option<(f32, Color)>,
+}
+
+impl std::fmt::Debug for TextParams<'_> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("TextParams")
+            .field("font", &self.font)
+            .field("font_scale", &self.font_scale)
+            .field("font_scale_aspect", &self.font_scale_aspect)
+            .field("rotation", &self.rotation)
+            .field("color", &self.color)
+            .field("enable_markup", &self.enable_markup)
+            .field("max_line_width", &self.max_line_width)
+            .field("truncate_ellipsis", &self.truncate_ellipsis)
+            .field("color_fn", &self.color_fn.map(|_| "Fn"))
+            .field("offset_fn", &self.offset_fn.map(|_| "Fn"))
+            .field("kerning", &self.kerning)
+            .field("shadow", &self.shadow)
+            .field("outline", &self.outline)
+            .finish()
+    }
 }
 
 impl<'a> Default for TextParams<'a> {
@@ -455,6 +686,12 @@ impl<'a> Default for TextParams<'a> {
             rotation: 0.0,
             enable_markup: true,
             max_line_width: None,
+            truncate_ellipsis: false,
+            color_fn: None,
+            offset_fn: None,
+            kerning: true,
+            shadow: None,
+            outline: None,
         }
     }
 }
@@ -468,18 +705,179 @@ pub fn load_ttf_font_from_bytes(font_size: f32, atlas: Texture2D, character_regi
     Ok(font)
 }
 
+/// Load a bitmap font from a prebaked `atlas` and its AngelCode BMFont text description (`.fnt`).
+///
+/// Only the `info`, `common`, `char` and `kerning` lines are used and only a single atlas page is supported.
+/// Unlike other fonts, bitmap fonts may have glyphs beyond extended ASCII, e.g. to be used with [Font::add_fallback].
+/// ```ignore
+/// let atlas = Texture2D::from_file_with_format(include_bytes!("pixel_font.png"));
+/// let font = load_bmfont(atlas, include_str!("pixel_font.fnt")).unwrap();
+/// ```
+pub fn load_bmfont(atlas: Texture2D, description: &str) -> Result<Font, Error> {
+    fn attribute<T: std::str::FromStr>(line: &str, key: &str) -> Option<T> {
+        line.split_whitespace()
+            .filter_map(|pair| pair.split_once('='))
+            .find(|(k, _)| *k == key)
+            .and_then(|(_, value)| value.trim_matches('"').parse().ok())
+    }
+
+    let mut font_size = None;
+    let mut line_height = None;
+    let mut base = None;
+    let mut glyphs = Vec::new();
+    let mut kerning = HashMap::new();
+
+    for line in description.lines().map(str::trim) {
+        match line.split_whitespace().next() {
+            Some("info") => font_size = attribute::<f32>(line, "size").map(f32::abs),
+            Some("common") => {
+                line_height = attribute::<f32>(line, "lineHeight");
+                base = attribute::<f32>(line, "base");
+            }
+            Some("char") => {
+                let (Some(id), Some(x), Some(y), Some(w), Some(h)) = (
+                    attribute::<u32>(line, "id"),
+                    attribute::<f32>(line, "x"),
+                    attribute::<f32>(line, "y"),
+                    attribute::<f32>(line, "width"),
+                    attribute::<f32>(line, "height"),
+                ) else {
+                    return Err(Error::FontError("bmfont char is missing id, x, y, width or height"));
+                };
+                let Some(c) = char::from_u32(id) else {
+                    continue;
+                };
+                glyphs.push((
+                    c,
+                    attribute::<f32>(line, "xoffset").unwrap_or(0.),
+                    attribute::<f32>(line, "yoffset").unwrap_or(0.),
+                    attribute::<f32>(line, "xadvance").unwrap_or(w),
+                    Rect::new(x, y, w, h),
+                ));
+            }
+            Some("kerning") => {
+                let (Some(first), Some(second), Some(amount)) = (
+                    attribute::<u32>(line, "first"),
+                    attribute::<u32>(line, "second"),
+                    attribute::<f32>(line, "amount"),
+                ) else {
+                    return Err(Error::FontError("bmfont kerning is missing first, second or amount"));
+                };
+                if let (Some(first), Some(second)) = (char::from_u32(first), char::from_u32(second)) {
+                    kerning.insert((first, second), amount);
+                }
+            }
+            _ => {}
+        }
+    }
+
+    let Some(base) = base else {
+        return Err(Error::FontError("bmfont description is missing the common line"));
+    };
+    if glyphs.is_empty() {
+        return Err(Error::FontError("bmfont description has no glyphs"));
+    }
+    if glyphs.len() >= u16::MAX as usize {
+        return Err(Error::FontError("bmfont description has too many glyphs"));
+    }
+
+    let mut index_map = [u16::MAX; 256];
+    let mut extra_glyphs = HashMap::new();
+    let character_regions = glyphs
+        .into_iter()
+        .enumerate()
+        .map(|(index, (c, xoffset, yoffset, advance, region))| {
+            match c as u32 {
+                code @ 0..=255 => index_map[code as usize] = index as u16,
+                _ => {
+                    extra_glyphs.insert(c, index as u16);
+                }
+            }
+            QuadFontCharacterInfo {
+                width: region.w as u8,
+                height: region.h as u8,
+                advance,
+                offset_x: xoffset,
+                // bmfont offsets are from the top of the line, glyphs are drawn relative to the baseline
+                offset_y: base - yoffset - region.h,
+                region,
+            }
+        })
+        .collect();
+
+    Ok(Font {
+        font_size: font_size.or(line_height).unwrap_or(base),
+        atlas,
+        character_regions,
+        index_map,
+        dpi_scale: 1.0,
+        kerning,
+        extra_glyphs,
+        fallbacks: vec![],
+    })
+}
+
 fn get_default_font() -> &'static Font {
     static DEFAULT: OnceLock<Font> = OnceLock::new();
-    DEFAULT.get_or_init(Font::default)
+
+    match &get_context().default_font {
+        Some(font) => font,
+        None => DEFAULT.get_or_init(Font::default),
+    }
 }
 
+/// Use `font` wherever no font is given, e.g. `draw_text` or `TextParams::font` and `measure_text` with `None`.
+pub fn set_default_font(font: Font) {
+    get_context().default_font = Some(font);
+}
+
+/// Go back to the built-in default font after `set_default_font`.
+pub fn reset_default_font() {
+    get_context().default_font = None;
+}
 
-pub fn draw_text_ex(text: impl AsRef<str>, x: f32, y: f32, params: TextParams) {
+/// Draws `text` with its baseline at `y` and returns its dimensions, the same as `measure_text_ex` would
+/// give for it but without laying the text out twice. Shadow and outline passes are not included.
+pub fn draw_text_ex(text: impl AsRef<str>, x: f32, y: f32, params: TextParams) -> TextDimensions {
     unsafe {
         let text = text.as_ref();
 
         if text.is_empty() {
-            return;
+            return TextDimensions::default();
+        }
+
+        if let Some(truncated) = truncate_with_ellipsis(text, &params) {
+            let params = TextParams {
+                max_line_width: None,
+                truncate_ellipsis: false,
+                ..params
+            };
+            return draw_text_ex(truncated, x, y, params);
+        }
+
+        if params.shadow.is_some() || params.outline.is_some() {
+            // solid color passes behind the text, the main pass below keeps the markup colors
+            let mut offsets = vec![];
+            if let Some((offset, color)) = params.shadow {
+                offsets.push((offset, color));
+            }
+            if let Some((thickness, color)) = params.outline {
+                for k in 0..8 {
+                    let angle = k as f32 * std::f32::consts::FRAC_PI_4;
+                    offsets.push((vec2(angle.cos(), angle.sin()) * thickness, color));
+                }
+            }
+
+            for (offset, color) in offsets {
+                let solid = move |_, _| color;
+                let pass = TextParams {
+                    color_fn: Some(&solid),
+                    shadow: None,
+                    outline: None,
+                    ..params.clone()
+                };
+                draw_text_ex(text, x + offset.x, y + offset.y, pass);
+            }
         }
 
         let font = match params.font {
@@ -487,14 +885,18 @@ pub fn draw_text_ex(text: impl AsRef<str>, x: f32, y: f32, params: TextParams) {
             None => get_default_font(),
         };
 
-        let dpi_scaling = 1.0; //miniquad::window::dpi_scale();
+        // same logical pixel layout as `Font::measure_text`, see there
+        let dpi_scaling = font.dpi_scale;
 
         let rot = params.rotation;
-        let font_scale_x = params.font_scale * params.font_scale_aspect;
-        let font_scale_y = params.font_scale;
-        let max_line_width_pixels = params.max_line_width.map(|w| w * dpi_scaling).unwrap_or(-1.);
+        let base_font_scale_x = params.font_scale * params.font_scale_aspect / dpi_scaling;
+        let base_font_scale_y = params.font_scale / dpi_scaling;
+        let mut font_scale_x = base_font_scale_x;
+        let mut font_scale_y = base_font_scale_y;
+        let max_line_width_pixels = params.max_line_width.unwrap_or(-1.);
 
         let mut current_line_scaled_width: f32 = 0.0; // Tracks width of the current line being built (scaled)
+        let mut lines = Vec::<Vec2>::new(); // (width, layout height) of the lines done, as in `TextDimensions`
 
         let mut max_offset_y_scaled: f32 = f32::MIN; // Scaled max offset from baseline
         let mut min_offset_y_scaled: f32 = f32::MAX; // Not directly used in TextDimensions, but calculated by render_character
@@ -509,8 +911,19 @@ pub fn draw_text_ex(text: impl AsRef<str>, x: f32, y: f32, params: TextParams) {
         let enable_markup = params.enable_markup;
 
         let original_color = params.color;
-        let mut color = original_color;
+        let mut paint = GlyphPaint {
+            color: original_color,
+            color_fn: params.color_fn,
+            offset_fn: params.offset_fn,
+            next_index: 0,
+            decorations: vec![],
+            decoration_runs: vec![],
+            trailing_whitespace: 0.,
+        };
         let mut color_stack = Vec::<Color>::with_capacity(4);
+        // scale of the size tags, see `apply_scale_markup`
+        let mut markup_scale = 1.;
+        let mut markup_scales = vec![];
 
         let mut current_x = x; // Screen-space X for drawing current char
         let mut current_y = y; // Screen-space Y for drawing current char (baseline)
@@ -521,19 +934,16 @@ pub fn draw_text_ex(text: impl AsRef<str>, x: f32, y: f32, params: TextParams) {
 
         let mut layout_line_height_scaled: f32 = 0.0; // The uniform scaled height for advancing lines
         if !text.is_empty() {
-            for character in chars.iter() {
-                let region = font.get_info(*character);
-                layout_line_height_scaled = layout_line_height_scaled.max(region.region.h * font_scale_y);
-            }
+            layout_line_height_scaled = font.tallest_glyph(&chars) * font_scale_y;
             if layout_line_height_scaled == 0.0 {
                 // Fallback if no glyphs or zero height glyphs
                 layout_line_height_scaled = font.font_size as f32 * font_scale_y * dpi_scaling;
             }
             if layout_line_height_scaled <= 0.0 {
                 // Ensure positive
-                layout_line_height_scaled = font.font_size as f32 * dpi_scaling;
+                layout_line_height_scaled = font.font_size as f32;
                 if layout_line_height_scaled <= 0.0 {
-                    layout_line_height_scaled = 1.0 * dpi_scaling;
+                    layout_line_height_scaled = 1.0;
                 }
             }
             layout_line_height_scaled += new_line_padding;
@@ -559,13 +969,14 @@ pub fn draw_text_ex(text: impl AsRef<str>, x: f32, y: f32, params: TextParams) {
                     rot_sin,
                     font_scale_x,
                     font_scale_y,
-                    dpi_scaling,
-                    color,
+                    &mut paint,
                 );
                 current_line_scaled_width += current_word_width_scaled;
                 word_buffer.set_len(0); // avoid .clear() to not drop contents since our types dont need dropping
                 current_word_width_scaled = 0.0;
 
+                lines.push(vec2(current_line_scaled_width, layout_line_height_scaled));
+                paint.trailing_whitespace = 0.;
                 current_x = start_x;
                 current_y += layout_line_height_scaled;
                 current_line_scaled_width = 0.0;
@@ -595,16 +1006,16 @@ pub fn draw_text_ex(text: impl AsRef<str>, x: f32, y: f32, params: TextParams) {
                             rot_sin,
                             font_scale_x,
                             font_scale_y,
-                            dpi_scaling,
-                            color,
+                            &mut paint,
                         );
                         current_line_scaled_width += current_word_width_scaled;
                         word_buffer.set_len(0); // avoid .clear() to not drop contents since our types dont need dropping
                         current_word_width_scaled = 0.0;
 
-                        color_stack.push(color);
+                        color_stack.push(paint.color);
+                        markup_scales.push(None);
                         if enable_markup {
-                            color = new_color;
+                            paint.color = new_color;
                         }
 
                         i = next_pos;
@@ -623,15 +1034,79 @@ pub fn draw_text_ex(text: impl AsRef<str>, x: f32, y: f32, params: TextParams) {
                             rot_sin,
                             font_scale_x,
                             font_scale_y,
-                            dpi_scaling,
-                            color,
+                            &mut paint,
+                        );
+                        current_line_scaled_width += current_word_width_scaled;
+                        word_buffer.set_len(0); // avoid .clear() to not drop contents since our types dont need dropping
+                        current_word_width_scaled = 0.0;
+
+                        // [] closes the innermost color or size tag
+                        if let Some(Some(_)) = markup_scales.last() {
+                            apply_scale_markup(&action, &mut markup_scale, &mut markup_scales);
+                            font_scale_x = base_font_scale_x * markup_scale;
+                            font_scale_y = base_font_scale_y * markup_scale;
+                        } else {
+                            markup_scales.pop();
+                            if enable_markup {
+                                paint.color = color_stack.pop().unwrap_or(original_color);
+                            }
+                        }
+
+                        i = next_pos;
+                        continue;
+                    }
+                    MarkupResult::Scale(_) => {
+                        render_word(
+                            font,
+                            &mut word_buffer,
+                            &mut current_x,
+                            &mut current_y,
+                            &mut max_offset_y_scaled,
+                            &mut min_offset_y_scaled,
+                            rot,
+                            rot_cos,
+                            rot_sin,
+                            font_scale_x,
+                            font_scale_y,
+                            &mut paint,
+                        );
+                        current_line_scaled_width += current_word_width_scaled;
+                        word_buffer.set_len(0); // avoid .clear() to not drop contents since our types dont need dropping
+                        current_word_width_scaled = 0.0;
+
+                        // applies whether markup is enabled or not, as `measure_text` doesn't know
+                        apply_scale_markup(&action, &mut markup_scale, &mut markup_scales);
+                        font_scale_x = base_font_scale_x * markup_scale;
+                        font_scale_y = base_font_scale_y * markup_scale;
+
+                        i = next_pos;
+                        continue;
+                    }
+                    MarkupResult::Decorate(decoration) | MarkupResult::Undecorate(decoration) => {
+                        render_word(
+                            font,
+                            &mut word_buffer,
+                            &mut current_x,
+                            &mut current_y,
+                            &mut max_offset_y_scaled,
+                            &mut min_offset_y_scaled,
+                            rot,
+                            rot_cos,
+                            rot_sin,
+                            font_scale_x,
+                            font_scale_y,
+                            &mut paint,
                         );
                         current_line_scaled_width += current_word_width_scaled;
                         word_buffer.set_len(0); // avoid .clear() to not drop contents since our types dont need dropping
                         current_word_width_scaled = 0.0;
 
                         if enable_markup {
-                            color = color_stack.pop().unwrap_or(original_color);
+                            if let MarkupResult::Decorate(_) = action {
+                                paint.decorations.push(decoration);
+                            } else if let Some(index) = paint.decorations.iter().rposition(|open| *open == decoration) {
+                                paint.decorations.remove(index);
+                            }
                         }
 
                         i = next_pos;
@@ -641,11 +1116,11 @@ pub fn draw_text_ex(text: impl AsRef<str>, x: f32, y: f32, params: TextParams) {
                 // Note: For Noop/Literal we do NOT set i = next_pos here to avoid skipping the next character.
             }
 
-            // char_data.advance is from fontdue for rasterized size (font_size, which is dpi-scaled)
-            // So, char_data.advance is in physical pixels for that rasterization.
-            // advance_scaled is thus physical_pixels * font_scale_x.
+            // info.advance is in pixels of the atlas, baked at font_size * dpi_scale.
+            // font_scale_x has the dpi scale divided out, so advance_scaled is in logical pixels.
             let info = font.get_info(c);
-            let advance_scaled = info.advance * font_scale_x;
+            let kerning_adjustment = if params.kerning { font.kerning_after(&chars, i, c) } else { 0. };
+            let advance_scaled = (info.advance + kerning_adjustment) * font_scale_x;
 
             if c == ' ' || c == '\t' || c == '-' {
                 // Word-breaking characters
@@ -661,8 +1136,7 @@ pub fn draw_text_ex(text: impl AsRef<str>, x: f32, y: f32, params: TextParams) {
                     rot_sin,
                     font_scale_x,
                     font_scale_y,
-                    dpi_scaling,
-                    color,
+                    &mut paint,
                 );
                 current_line_scaled_width += current_word_width_scaled;
                 word_buffer.set_len(0);
@@ -670,6 +1144,9 @@ pub fn draw_text_ex(text: impl AsRef<str>, x: f32, y: f32, params: TextParams) {
 
                 if max_line_width_pixels != -1.0 {
                     if current_line_scaled_width + advance_scaled > max_line_width_pixels && current_line_scaled_width > 0.0 {
+                        // trailing whitespace isn't part of the wrapped line, as in `Font::measure_text`
+                        let trimmed_width = current_line_scaled_width - paint.trailing_whitespace;
+                        lines.push(vec2(trimmed_width, layout_line_height_scaled));
                         current_x = start_x;
                         current_y += layout_line_height_scaled;
                         current_line_scaled_width = 0.0;
@@ -684,6 +1161,7 @@ pub fn draw_text_ex(text: impl AsRef<str>, x: f32, y: f32, params: TextParams) {
 
                 render_character(
                     c,
+                    advance_scaled,
                     font,
                     current_x,
                     current_y,
@@ -691,8 +1169,7 @@ pub fn draw_text_ex(text: impl AsRef<str>, x: f32, y: f32, params: TextParams) {
                     rot_sin,
                     font_scale_x,
                     font_scale_y,
-                    dpi_scaling,
-                    color,
+                    &mut paint,
                     &mut max_offset_y_scaled,
                     &mut min_offset_y_scaled,
                     rot,
@@ -704,18 +1181,21 @@ pub fn draw_text_ex(text: impl AsRef<str>, x: f32, y: f32, params: TextParams) {
                 if max_line_width_pixels != -1.0 {
                     if current_line_scaled_width + current_word_width_scaled + advance_scaled > max_line_width_pixels {
                         if current_line_scaled_width > 0.0 {
+                            lines.push(vec2(current_line_scaled_width, layout_line_height_scaled));
                             current_x = start_x;
                             current_y += layout_line_height_scaled;
                             current_line_scaled_width = 0.0;
                         } else {
                             for (_buffered_char, buffered_advance) in word_buffer.drain(..) {
                                 if current_line_scaled_width + buffered_advance > max_line_width_pixels && current_line_scaled_width > 0.0 {
+                                    lines.push(vec2(current_line_scaled_width, layout_line_height_scaled));
                                     current_x = start_x;
                                     current_y += layout_line_height_scaled;
                                     current_line_scaled_width = 0.0;
                                 }
                                 render_character(
                                     _buffered_char,
+                                    buffered_advance,
                                     font,
                                     current_x,
                                     current_y,
@@ -723,8 +1203,7 @@ pub fn draw_text_ex(text: impl AsRef<str>, x: f32, y: f32, params: TextParams) {
                                     rot_sin,
                                     font_scale_x,
                                     font_scale_y,
-                                    dpi_scaling,
-                                    color,
+                                    &mut paint,
                                     &mut max_offset_y_scaled,
                                     &mut min_offset_y_scaled,
                                     rot,
@@ -735,6 +1214,7 @@ pub fn draw_text_ex(text: impl AsRef<str>, x: f32, y: f32, params: TextParams) {
                             current_word_width_scaled = 0.0;
 
                             if current_line_scaled_width + advance_scaled > max_line_width_pixels && current_line_scaled_width > 0.0 {
+                                lines.push(vec2(current_line_scaled_width, layout_line_height_scaled));
                                 current_x = start_x;
                                 current_y += layout_line_height_scaled;
                                 current_line_scaled_width = 0.0;
@@ -760,12 +1240,89 @@ pub fn draw_text_ex(text: impl AsRef<str>, x: f32, y: f32, params: TextParams) {
             rot_sin,
             font_scale_x,
             font_scale_y,
-            dpi_scaling,
-            color,
+            &mut paint,
         );
+
+        // decorations are drawn over the glyphs along the lines, they don't follow `rotation`
+        let thickness = (font.font_size * params.font_scale / 14.).max(1.);
+        let half_x_height = font.get_info('x').region.h * font_scale_y / 2.;
+        for run in &paint.decoration_runs {
+            let y = match run.decoration {
+                TextDecoration::Underline => run.y + thickness,
+                TextDecoration::Strikethrough => run.y - half_x_height - thickness / 2.,
+            };
+            crate::shapes::draw_rectangle(run.x, y, run.width, thickness, run.color);
+        }
+
+        current_line_scaled_width += current_word_width_scaled;
+        if current_line_scaled_width > 0.0 || lines.is_empty() {
+            lines.push(vec2(current_line_scaled_width, layout_line_height_scaled));
+        }
+
+        TextDimensions {
+            width: lines.iter().fold(0., |width, line| line.x.max(width)),
+            height: lines.len() as f32 * layout_line_height_scaled,
+            offset_y: if max_offset_y_scaled == f32::MIN { 0. } else { max_offset_y_scaled },
+            per_line_dimensions: lines,
+        }
     }
 }
 
+/// Color of the glyphs being drawn along with the per glyph effects from `TextParams`.
+struct GlyphPaint<'a> {
+    /// Color from `TextParams` or the innermost markup color tag.
+    color: Color,
+    color_fn: Option<&'a dyn Fn(usize, char) -> Color>,
+    offset_fn: Option<&'a dyn Fn(usize, char) -> Vec2>,
+    /// Index of the next glyph drawn, markup tags excluded.
+    next_index: usize,
+    /// Decoration tags open at the glyphs being drawn, innermost last.
+    decorations: Vec<TextDecoration>,
+    /// Decorated stretches of the glyphs drawn so far, drawn once all glyphs are.
+    decoration_runs: Vec<DecorationRun>,
+    /// Advance of the spaces and tabs drawn since the last other glyph.
+    trailing_whitespace: f32,
+}
+
+impl GlyphPaint<'_> {
+    /// Extends the run of `decoration` ending at `pen` by `advance`, or starts a new run there.
+    fn decorate(&mut self, decoration: TextDecoration, pen: Vec2, advance: f32, color: Color) {
+        // underline and strikethrough runs are built side by side
+        let run = self
+            .decoration_runs
+            .iter_mut()
+            .rev()
+            .take(2)
+            .find(|run| run.decoration == decoration);
+        match run {
+            Some(run) if run.y == pen.y && run.color == color && (run.x + run.width - pen.x).abs() < 0.01 => run.width += advance,
+            _ => self.decoration_runs.push(DecorationRun {
+                decoration,
+                x: pen.x,
+                y: pen.y,
+                width: advance,
+                color,
+            }),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum TextDecoration {
+    Underline,
+    Strikethrough,
+}
+
+/// A line under or through glyphs drawn next to each other with the same color.
+struct DecorationRun {
+    decoration: TextDecoration,
+    x: f32,
+    /// Baseline of the glyphs.
+    y: f32,
+    width: f32,
+    color: Color,
+}
+
 // Make sure `parse_markup`, `render_word`, `render_character`, `MarkupResult`, `get_context`, `CharacterInfo`, `Color`
 // and `smallvec::SmallVec` are correctly defined and in scope.
 // The `render_word` and `render_character` helpers would use `max_offset_y_scaled` and `min_offset_y_scaled`.
@@ -782,12 +1339,12 @@ fn render_word(
     rot_sin: f32,
     font_scale_x: f32,
     font_scale_y: f32,
-    dpi_scaling: f32,
-    color: Color,
+    paint: &mut GlyphPaint,
 ) {
     for (c, advance) in word_buffer.iter() {
         render_character(
             *c,
+            *advance,
             font,
             *current_x,
             *current_y,
@@ -795,8 +1352,7 @@ fn render_word(
             rot_sin,
             font_scale_x,
             font_scale_y,
-            dpi_scaling,
-            color,
+            paint,
             max_offset_y,
             min_offset_y,
             rot,
@@ -809,6 +1365,7 @@ fn render_word(
 // Helper function to render a single character
 fn render_character(
     char: char,
+    advance: f32,
     font: &Font,
     current_x: f32,
     current_y: f32,
@@ -816,13 +1373,29 @@ fn render_character(
     rot_sin: f32,
     font_scale_x: f32,
     font_scale_y: f32,
-    dpi_scaling: f32,
-    color: Color,
+    paint: &mut GlyphPaint,
     max_offset_y: &mut f32,
     min_offset_y: &mut f32,
     rot: f32,
 ) {
-    let info = font.get_info(char);
+    let index = paint.next_index;
+    paint.next_index += 1;
+    if char == ' ' || char == '\t' {
+        paint.trailing_whitespace += advance;
+    } else {
+        paint.trailing_whitespace = 0.;
+    }
+    let color = paint.color_fn.map_or(paint.color, |color_fn| color_fn(index, char));
+    for decoration in [TextDecoration::Underline, TextDecoration::Strikethrough] {
+        if paint.decorations.contains(&decoration) {
+            paint.decorate(decoration, vec2(current_x, current_y), advance, color);
+        }
+    }
+    let glyph_offset = paint.offset_fn.map_or(vec2(0., 0.), |offset_fn| offset_fn(index, char));
+    let current_x = current_x + glyph_offset.x;
+    let current_y = current_y + glyph_offset.y;
+
+    let (glyph_font, info) = font.glyph(char);
     let glyph = info.region;
 
     let glyph_scaled_h = glyph.h * font_scale_y;
@@ -837,14 +1410,14 @@ fn render_character(
     let dest_y = (offset_x) * rot_sin + (-glyph_scaled_h - offset_y) * rot_cos;
 
     let dest = Rect::new(
-        dest_x / dpi_scaling + current_x,
-        dest_y / dpi_scaling + current_y,
-        glyph.w / dpi_scaling * font_scale_x,
-        glyph.h / dpi_scaling * font_scale_y,
+        dest_x + current_x,
+        dest_y + current_y,
+        glyph.w * font_scale_x,
+        glyph.h * font_scale_y,
     );
 
     crate::texture::draw_texture_ex(
-        &font.atlas,
+        &glyph_font.atlas,
         dest.x,
         dest.y,
         color,
@@ -857,6 +1430,93 @@ fn render_character(
     );
 }
 
+/// What `draw_text_centered` does with text larger than its bounds.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum TextOverflow {
+    /// Draw the text as is, past the bounds.
+    #[default]
+    Visible,
+    /// Cut the text at the bounds. Assumes drawing to the screen rather than to a render target.
+    Clip,
+    /// Scale the text down until it fits.
+    ScaleDown,
+}
+
+/// Draw text centered horizontally and vertically within `bounds`.
+/// `params.max_line_width` may be used to wrap it.
+pub fn draw_text_centered(text: impl AsRef<str>, bounds: Rect, params: TextParams, overflow: TextOverflow) {
+    let text = text.as_ref();
+    let font = params.font.unwrap_or_else(|| get_default_font());
+    let mut params = params;
+
+    let (max_line_width, kerning) = (params.max_line_width, params.kerning);
+    let measure = |font_scale: f32| font.measure_text(text, font.font_size as u16, Vec2::splat(font_scale), max_line_width, kerning, None);
+
+    let mut dimensions = measure(params.font_scale);
+    if overflow == TextOverflow::ScaleDown && dimensions.width > 0. && dimensions.height > 0. {
+        let fit = (bounds.w / dimensions.width).min(bounds.h / dimensions.height);
+        if fit < 1. {
+            params.font_scale *= fit;
+            dimensions = measure(params.font_scale);
+        }
+    }
+
+    let x = bounds.center().x - dimensions.width / 2.;
+    let y = bounds.center().y - dimensions.height / 2. + dimensions.offset_y;
+
+    if overflow != TextOverflow::Clip {
+        draw_text_ex(text, x, y, params);
+        return;
+    }
+
+    let context = get_context();
+    let previous_clip = context.gl.get_scissor();
+    context.gl.scissor(Some(screen_clip(bounds)));
+    draw_text_ex(text, x, y, params);
+    get_context().gl.scissor(previous_clip);
+}
+
+/// Same as `draw_text_ex`, with each line drawn over a `highlight` filled rect as wide as the line,
+/// e.g. for chat bubbles or selected lines in an editor. Wrapping by `params.max_line_width` is
+/// taken into account, `params.rotation` is not.
+pub fn draw_text_highlighted(text: impl AsRef<str>, x: f32, y: f32, highlight: Color, params: TextParams) {
+    let text = text.as_ref();
+    let font = params.font.unwrap_or_else(|| get_default_font());
+    let scale = vec2(params.font_scale * params.font_scale_aspect, params.font_scale);
+    let dimensions = font.measure_text(text, font.font_size as u16, scale, params.max_line_width, params.kerning, None);
+
+    let mut top = y - dimensions.offset_y;
+    for line in &dimensions.per_line_dimensions {
+        crate::shapes::draw_rectangle(x, top, line.x, line.y, highlight);
+        top += line.y;
+    }
+
+    draw_text_ex(text, x, y, params);
+}
+
+/// Scissor rect, in physical pixels of the frame, covering `rect` as seen through the active camera.
+fn screen_clip(rect: Rect) -> (i32, i32, i32, i32) {
+    let context = get_context();
+    let projection = context.projection_matrix();
+    let scale = context.render_scale;
+    let size = vec2(context.screen_width, context.screen_height) * scale;
+
+    let corners = [
+        rect.point(),
+        vec2(rect.right(), rect.y),
+        rect.point() + rect.size(),
+        vec2(rect.x, rect.bottom()),
+    ];
+    let corners = corners.map(|corner| {
+        let ndc = projection.project_point3(vec3(corner.x, corner.y, 0.));
+        vec2(ndc.x + 1., 1. - ndc.y) / 2. * size
+    });
+    let min = corners.iter().fold(corners[0], |min, corner| min.min(*corner));
+    let size = corners.iter().fold(corners[0], |max, corner| max.max(*corner)) - min;
+
+    (min.x as i32, min.y as i32, size.x.ceil() as i32, size.y.ceil() as i32)
+}
+
 /// Get the text center.
 pub fn get_text_center(
     text: impl AsRef<str>,
@@ -881,9 +1541,89 @@ pub fn measure_text(
     font_scale: f32,
     max_line_width_unscaled: Option<f32>,
 ) -> TextDimensions {
-    let font = unsafe { font.unwrap_unchecked() };
+    let font = font.unwrap_or_else(|| get_default_font());
 
-    font.measure_text(text, font_size, font_scale, font_scale, max_line_width_unscaled)
+    font.measure_text(text, font_size, Vec2::splat(font_scale), max_line_width_unscaled, true, None)
+}
+
+/// Measures `text` the way `draw_text_ex` lays it out with `params`, including `truncate_ellipsis`
+/// and `font_scale_aspect` which `measure_text` doesn't know about.
+pub fn measure_text_ex(text: impl AsRef<str>, params: &TextParams) -> TextDimensions {
+    let text = text.as_ref();
+    let font = params.font.unwrap_or_else(|| get_default_font());
+    let scale = vec2(params.font_scale * params.font_scale_aspect, params.font_scale);
+
+    match truncate_with_ellipsis(text, params) {
+        Some(truncated) => font.measure_text(truncated, font.font_size as u16, scale, None, params.kerning, None),
+        None => font.measure_text(text, font.font_size as u16, scale, params.max_line_width, params.kerning, None),
+    }
+}
+
+/// `text` cut to fit `params.max_line_width` on a single line and ended with an ellipsis,
+/// if `params.truncate_ellipsis` applies and the text is too wide.
+///
+/// Markup tags are never cut. When not even the ellipsis fits, only what fits of it is kept.
+fn truncate_with_ellipsis(text: &str, params: &TextParams) -> Option<String> {
+    let max_width = params.max_line_width.filter(|_| params.truncate_ellipsis && !text.contains('\n'))?;
+    let font = params.font.unwrap_or_else(|| get_default_font());
+    let scale = vec2(params.font_scale * params.font_scale_aspect, params.font_scale);
+    let font_size = font.font_size as u16;
+    let fits = |text: &str| font.measure_text(text, font_size, scale, None, params.kerning, None).width <= max_width;
+
+    if fits(text) {
+        return None;
+    }
+
+    let chars: Vec<char> = text.chars().collect();
+    // char index after each visible char, cutting there keeps the tags before it whole
+    let mut ends = vec![0];
+    let mut i = 0;
+    while i < chars.len() {
+        if chars[i] == '[' {
+            let (action, next_pos) = parse_markup(&chars, i);
+            if !matches!(action, MarkupResult::Literal(_) | MarkupResult::Noop) {
+                i = next_pos;
+                continue;
+            }
+        }
+        i += 1;
+        ends.push(i);
+    }
+
+    let ellipsis = if font.find_glyph('…').is_some() { "…" } else { "..." };
+    let truncated = |end: usize, ellipsis: &str| {
+        let prefix: String = chars[..end].iter().collect();
+        format!("{}{}", prefix.trim_end(), ellipsis)
+    };
+
+    if !fits(&truncated(0, ellipsis)) {
+        let mut parts = ellipsis.char_indices().rev().map(|(len, _)| &ellipsis[..len]);
+        return Some(parts.find(|part| fits(part)).unwrap_or_default().to_string());
+    }
+
+    // the longest prefix that fits with the ellipsis, the empty one does
+    let fitting = ends[1..].partition_point(|end| fits(&truncated(*end, ellipsis)));
+    Some(truncated(ends[fitting], ellipsis))
+}
+
+/// Returns where `measure_text` and `draw_text_ex` wrap `text` to fit `max_line_width_unscaled`.
+///
+/// Each value is the index of the first char of a wrapped line, counting the chars of `text`
+/// without markup tags. Only soft breaks are reported, explicit '\n' are not.
+/// A space or tab swallowed at a break is not part of either line.
+pub fn measure_text_breaks(
+    text: impl AsRef<str>,
+    font: Option<&Font>,
+    font_size: u16,
+    font_scale: f32,
+    max_line_width_unscaled: f32,
+) -> Vec<usize> {
+    let font = font.unwrap_or_else(|| get_default_font());
+    let max_line_width = Some(max_line_width_unscaled);
+    let mut breaks = vec![];
+
+    font.measure_text(text, font_size, Vec2::splat(font_scale), max_line_width, true, Some(&mut breaks));
+    breaks
 }
 
 /// From given font size in world space gives
@@ -902,13 +1642,245 @@ pub fn camera_font_scale(world_font_size: f32) -> (u16, f32, f32) {
     (font_size, cam_h / scr_h, scr_h / scr_w * cam_w / cam_h)
 }
 
+/// A run of text drawn in one style, see [parse_markup_spans].
+#[derive(Debug, Clone, PartialEq)]
+pub struct TextSpan {
+    pub text: String,
+    /// Color of the innermost markup color tag, `None` outside of tags where `TextParams::color` is used.
+    pub color: Option<Color>,
+    /// Inside a [u] tag.
+    pub underline: bool,
+    /// Inside a [s] tag.
+    pub strikethrough: bool,
+    /// Product of the enclosing [size=..] tags, 1 outside of them.
+    pub scale: f32,
+}
+
+/// Splits `text` into the runs `draw_text_ex` draws in the same style with markup enabled, without drawing,
+/// e.g. for a rich text editor preview.
+///
+/// Tags are removed, malformed ones are kept as literal text like when drawing.
+pub fn parse_markup_spans(text: &str) -> Vec<TextSpan> {
+    let chars: Vec<char> = text.chars().collect();
+    let mut spans = Vec::new();
+    let mut colors = Vec::<Color>::new();
+    let mut decorations = Vec::<TextDecoration>::new();
+    let mut scale = 1.;
+    let mut scales = vec![];
+    let mut current = String::new();
+    let span = |text, colors: &[Color], decorations: &[TextDecoration], scale| TextSpan {
+        text,
+        color: colors.last().copied(),
+        underline: decorations.contains(&TextDecoration::Underline),
+        strikethrough: decorations.contains(&TextDecoration::Strikethrough),
+        scale,
+    };
+
+    let mut i = 0;
+    while i < chars.len() {
+        let mut c = chars[i];
+
+        if c == '[' {
+            let (action, next_pos) = parse_markup(&chars, i);
+            match action {
+                MarkupResult::Noop => {}
+                // like draw_text_ex, only the bracket is consumed
+                MarkupResult::Literal(char_literal) => c = char_literal,
+                _ => {
+                    if !current.is_empty() {
+                        spans.push(span(std::mem::take(&mut current), &colors, &decorations, scale));
+                    }
+                    // [] closes the innermost color or size tag
+                    let closes_size_tag = matches!(scales.last(), Some(Some(_)));
+                    apply_scale_markup(&action, &mut scale, &mut scales);
+                    match action {
+                        MarkupResult::Push(color) => colors.push(color),
+                        MarkupResult::Pop if !closes_size_tag => {
+                            colors.pop();
+                        }
+                        MarkupResult::Decorate(decoration) => decorations.push(decoration),
+                        MarkupResult::Undecorate(decoration) => {
+                            if let Some(index) = decorations.iter().rposition(|open| *open == decoration) {
+                                decorations.remove(index);
+                            }
+                        }
+                        _ => {}
+                    }
+
+                    i = next_pos;
+                    continue;
+                }
+            }
+        }
+
+        current.push(c);
+        i += 1;
+    }
+
+    if !current.is_empty() {
+        spans.push(span(current, &colors, &decorations, scale));
+    }
+    spans
+}
+
+#[test]
+fn size_markup_measures_like_font_scale() {
+    let regions = (0..256)
+        .map(|code| QuadFontCharacterInfo {
+            width: 8,
+            height: 10,
+            advance: 8. + (code % 3) as f32,
+            offset_x: 0.,
+            offset_y: -((code % 2) as f32),
+            region: Rect::new(0., 0., 8., 10. + (code % 5) as f32),
+        })
+        .collect();
+    let font = Font::load_from_bytes(16., Texture2D::detached(), regions).unwrap();
+
+    let tagged = font.measure_text("[size=2]AB[]", 16, Vec2::splat(1.), None, true, None);
+    let scaled = font.measure_text("AB", 16, Vec2::splat(2.), None, true, None);
+    assert_eq!(tagged, scaled);
+
+    let spans = parse_markup_spans("a[size=2]b[#ff0000]c[]d[]e");
+    let scales: Vec<(&str, f32)> = spans.iter().map(|span| (span.text.as_str(), span.scale)).collect();
+    assert_eq!(scales, [("a", 1.), ("b", 2.), ("c", 2.), ("d", 2.), ("e", 1.)]);
+}
+
+#[test]
+fn ellipsis_truncation() {
+    let regions = (0..256)
+        .map(|_| QuadFontCharacterInfo {
+            width: 8,
+            height: 10,
+            advance: 8.,
+            offset_x: 0.,
+            offset_y: 0.,
+            region: Rect::new(0., 0., 8., 10.),
+        })
+        .collect();
+    let font = Font::load_from_bytes(16., Texture2D::detached(), regions).unwrap();
+    let params = |max_line_width| TextParams {
+        font: Some(&font),
+        max_line_width: Some(max_line_width),
+        truncate_ellipsis: true,
+        ..Default::default()
+    };
+
+    assert_eq!(truncate_with_ellipsis("Hello", &params(40.)), None);
+    assert_eq!(truncate_with_ellipsis("Hello world", &params(40.)).as_deref(), Some("He..."));
+    let tagged = truncate_with_ellipsis("[#ff0000]Hello[] world", &params(40.));
+    assert_eq!(tagged.as_deref(), Some("[#ff0000]He..."));
+    assert_eq!(truncate_with_ellipsis("Hello world", &params(20.)).as_deref(), Some(".."));
+    assert_eq!(measure_text_ex("Hello world", &params(40.)).width, 40.);
+}
+
+#[test]
+fn rebuild_for_dpi_keeps_glyph_map() {
+    let description = "common lineHeight=20 base=16\n\
+        char id=65 x=0 y=0 width=8 height=10 xoffset=0 yoffset=6 xadvance=9\n\
+        char id=8364 x=8 y=0 width=8 height=10 xoffset=0 yoffset=6 xadvance=9\n\
+        kerning first=65 second=8364 amount=-1";
+    let mut font = load_bmfont(Texture2D::detached(), description).unwrap();
+
+    let regions: Vec<_> = font
+        .character_regions
+        .iter()
+        .map(|info| QuadFontCharacterInfo {
+            region: Rect {
+                w: info.region.w * 2.,
+                ..info.region
+            },
+            ..info.clone()
+        })
+        .collect();
+    assert!(font.rebuild_for_dpi(2., Texture2D::detached(), regions[..1].to_vec()).is_err());
+    assert_eq!(font.dpi_scale(), 1.);
+
+    font.rebuild_for_dpi(2., Texture2D::detached(), regions).unwrap();
+    assert_eq!(font.dpi_scale(), 2.);
+    assert_eq!(font.own_glyph('€').map(|info| info.region.w), Some(16.));
+    assert_eq!(font.own_glyph('A').map(|info| info.region.w), Some(16.));
+    assert_eq!(font.kerning('A', '€'), -1.);
+}
+
+#[test]
+fn markup_spans() {
+    let red = Color::new(1., 0., 0., 1.);
+    let spans = parse_markup_spans("a [#ff0000]red [#00ff0080]x[] b[] c [#zz] [[");
+
+    let texts: Vec<&str> = spans.iter().map(|span| span.text.as_str()).collect();
+    assert_eq!(texts, ["a ", "red ", "x", " b", " c [#zz] [["]);
+    assert_eq!(spans[0].color, None);
+    assert_eq!(spans[1].color, Some(red));
+    assert_eq!(spans[2].color.map(|color| color.g), Some(1.));
+    assert_eq!(spans[3].color, Some(red));
+    assert_eq!(spans[4].color, None);
+
+    let spans = parse_markup_spans("[u]a [s]b[/u] c[/s][/x]");
+    let styles: Vec<(&str, bool, bool)> = spans
+        .iter()
+        .map(|span| (span.text.as_str(), span.underline, span.strikethrough))
+        .collect();
+    assert_eq!(
+        styles,
+        [("a ", true, false), ("b", true, true), (" c", false, true), ("[/x]", false, false)]
+    );
+}
+
 enum MarkupResult {
     Literal(char),
     Pop,
     Push(Color),
+    Decorate(TextDecoration),
+    Undecorate(TextDecoration),
+    Scale(f32),
     Noop,
 }
 
+/// Updates `scale` for a size tag or the `[]` closing one. `scales` holds the scale to restore
+/// for each open color or size tag, `None` for color tags.
+fn apply_scale_markup(action: &MarkupResult, scale: &mut f32, scales: &mut Vec<Option<f32>>) {
+    match *action {
+        MarkupResult::Push(_) => scales.push(None),
+        MarkupResult::Scale(tag_scale) => {
+            scales.push(Some(*scale));
+            *scale *= tag_scale;
+        }
+        MarkupResult::Pop => {
+            if let Some(Some(previous)) = scales.pop() {
+                *scale = previous;
+            }
+        }
+        _ => {}
+    }
+}
+
+/// Scale of a `size=1.5]` tag at the start of `chars`, after the opening bracket, and the tag length.
+fn parse_size_tag(chars: &[char]) -> Option<(f32, usize)> {
+    const PREFIX: &str = "size=";
+    if !PREFIX.chars().enumerate().all(|(k, c)| chars.get(k) == Some(&c)) {
+        return None;
+    }
+
+    let value_len = chars[PREFIX.len()..].iter().take(16).position(|c| *c == ']')?;
+    let value: String = chars[PREFIX.len()..PREFIX.len() + value_len].iter().collect();
+    let scale = value.parse::<f32>().ok().filter(|scale| scale.is_finite() && *scale > 0.)?;
+    Some((scale, PREFIX.len() + value_len + 1))
+}
+
+/// Decoration tag at the start of `chars`, after the opening bracket, and its length.
+fn parse_decoration_tag(chars: &[char]) -> Option<(MarkupResult, usize)> {
+    let tags = [
+        ("u]", MarkupResult::Decorate(TextDecoration::Underline)),
+        ("/u]", MarkupResult::Undecorate(TextDecoration::Underline)),
+        ("s]", MarkupResult::Decorate(TextDecoration::Strikethrough)),
+        ("/s]", MarkupResult::Undecorate(TextDecoration::Strikethrough)),
+    ];
+    tags.into_iter()
+        .find(|(tag, _)| tag.chars().enumerate().all(|(k, c)| chars.get(k) == Some(&c)))
+        .map(|(tag, result)| (result, tag.len()))
+}
+
 fn parse_markup(chars: &[char], pos: usize) -> (MarkupResult, usize) {
     let length = chars.len();
 
@@ -924,70 +1896,20 @@ fn parse_markup(chars: &[char], pos: usize) -> (MarkupResult, usize) {
                 // This would be a pop
                 return (MarkupResult::Pop, pos + 2);
             } else if chars[pos + 1] == '#' {
-                // We're now going to parse a color tag (either RRGGBBAA (8) or RRGGBB (6) where AA is set to FF)
-                let mut rgba = 0;
-                let mut shift = 32; // 32 bits (we work with nibbles, not bytes)
-
-                // Parse the RGBA color tag.
-                for x in (pos + 2)..(pos + 2 + 8 + 1) {
-                    if x < length {
-                        let char = chars[x];
-
-                        // If we end the batch, ensure we have either 6 or 8 chars or it won't be valid.
-                        if char == ']' {
-                            // Shift is 4 in RRGGBB and -4 in RRGGBBAA.
-                            if shift == 8 {
-                                // 6 chars because RRGGBB
-                                rgba |= 0xFF;
-
-                                return (
-                                    MarkupResult::Push(Color {
-                                        r: ((rgba >> 24) & 0xFF) as f32 / 255.0,
-                                        g: ((rgba >> 16) & 0xFF) as f32 / 255.0,
-                                        b: ((rgba >> 8) & 0xFF) as f32 / 255.0,
-                                        a: (rgba & 0xFF) as f32 / 255.0,
-                                    }),
-                                    pos + 2 + 6 + 1,
-                                );
-                            } else if shift == 0 {
-                                // 8 chars because RRGGBBAA
-                                return (
-                                    MarkupResult::Push(Color {
-                                        r: ((rgba >> 24) & 0xFF) as f32 / 255.0,
-                                        g: ((rgba >> 16) & 0xFF) as f32 / 255.0,
-                                        b: ((rgba >> 8) & 0xFF) as f32 / 255.0,
-                                        a: (rgba & 0xFF) as f32 / 255.0,
-                                    }),
-                                    pos + 2 + 8 + 1,
-                                );
-                            } else {
-                                // Wrong shift. Literal.
-                                return (MarkupResult::Literal('['), pos + 1);
-                            }
-                        }
-
-                        // Parse hex nibble from the char given
-                        let nibble = if ('a'..='f').contains(&char) {
-                            10 + char as u8 - b'a'
-                        } else if char.is_ascii_digit() {
-                            char as u8 - b'0'
-                        } else if ('A'..='F').contains(&char) {
-                            10 + char as u8 - b'A'
-                        } else {
-                            // Don't parse this block anymore because it's corrupt.
-                            return (MarkupResult::Literal('['), pos + 1);
-                        };
-
-                        shift -= 4;
-                        rgba |= (nibble as u32) << shift;
-                    } else {
-                        // It was out of bounds, so it's taken literally.
-                        return (MarkupResult::Literal('['), pos + 1);
+                // A color tag, [#RRGGBB] or [#RRGGBBAA]
+                if let Some(end) = chars[pos + 1..].iter().take(10).position(|c| *c == ']') {
+                    let tag: String = chars[pos + 1..pos + 1 + end].iter().collect();
+                    if let Some(color) = Color::from_hex_str(&tag) {
+                        return (MarkupResult::Push(color), pos + 2 + end);
                     }
                 }
 
-                // Assume the color tag parsing went wrong and take the bracket literally.
+                // Not a valid color tag, take the bracket literally.
                 return (MarkupResult::Literal('['), pos + 1);
+            } else if let Some((decoration, tag_len)) = parse_decoration_tag(&chars[pos + 1..]) {
+                return (decoration, pos + 1 + tag_len);
+            } else if let Some((scale, tag_len)) = parse_size_tag(&chars[pos + 1..]) {
+                return (MarkupResult::Scale(scale), pos + 1 + tag_len);
             }
         }
     }
@@ -1,12 +1,12 @@
 //! Loading and rendering textures. Also render textures, per-pixel image manipulations.
 
-use crate::{color::Color, get_context, get_quad_context, math::Rect, Error};
+use crate::{color::Color, get_context, get_quad_context, logging::warn, math::Rect, Error};
 use std::fs::File;
 use std::io::{BufWriter, Cursor};
 
 pub use crate::quad_gl::FilterMode;
 use crate::quad_gl::{DrawMode, Vertex};
-use glam::{vec2, Vec2};
+use glam::{vec2, Affine2, Vec2};
 use png::{BitDepth, ColorType, Decoder, Encoder};
 
 /// Set the Z depth value used for all subsequent draw calls.
@@ -16,6 +16,7 @@ pub fn set_draw_depth(z: f32) {
     context.gl.set_draw_depth(z);
 }
 use slotmap::{TextureIdSlotMap, TextureSlotId};
+use std::collections::HashMap;
 use std::sync::Arc;
 
 mod slotmap;
@@ -114,8 +115,8 @@ impl Image {
 
     /// Creates an Image from a slice of bytes that contains an encoded image.
     ///
-    /// If `format` is None, it will make an educated guess on the
-    /// [ImageFormat][image::ImageFormat].
+    /// The format is detected from the first bytes of the file. PNG is always supported,
+    /// JPEG requires the `jpeg` feature and WebP the `webp` feature.
     ///
     /// # Example
     ///
@@ -126,9 +127,27 @@ impl Image {
     ///     );
     /// ```
     pub fn from_file_with_format(bytes: &[u8]) -> Result<Image, Error> {
+        if bytes.starts_with(b"\x89PNG") {
+            Self::decode_png(bytes)
+        } else if bytes.starts_with(&[0xFF, 0xD8, 0xFF]) {
+            #[cfg(feature = "jpeg")]
+            return Self::decode_jpeg(bytes);
+            #[cfg(not(feature = "jpeg"))]
+            return Err(Error::UnsupportedImageFormat("JPEG decoding requires the `jpeg` feature"));
+        } else if bytes.len() >= 12 && bytes.starts_with(b"RIFF") && &bytes[8..12] == b"WEBP" {
+            #[cfg(feature = "webp")]
+            return Self::decode_webp(bytes);
+            #[cfg(not(feature = "webp"))]
+            return Err(Error::UnsupportedImageFormat("WebP decoding requires the `webp` feature"));
+        } else {
+            Err(Error::UnsupportedImageFormat("unknown image format, expected PNG, JPEG or WebP"))
+        }
+    }
+
+    fn decode_png(bytes: &[u8]) -> Result<Image, Error> {
         let mut decoder = Decoder::new(Cursor::new(bytes));
         decoder.set_transformations(png::Transformations::EXPAND | png::Transformations::ALPHA);
-        let mut reader = decoder.read_info().expect("Failed to read image info");
+        let mut reader = decoder.read_info()?;
 
         let output_size = reader.output_buffer_size().expect("Should have output size");
         let mut buf = vec![0; output_size];
@@ -141,6 +160,82 @@ impl Image {
         Ok(Image { width, height, bytes })
     }
 
+    #[cfg(feature = "jpeg")]
+    fn decode_jpeg(bytes: &[u8]) -> Result<Image, Error> {
+        use jpeg_decoder::PixelFormat;
+
+        let mut decoder = jpeg_decoder::Decoder::new(bytes);
+        let pixels = decoder.decode()?;
+        let info = decoder.info().expect("Image info is known after decoding");
+
+        let bytes = match info.pixel_format {
+            PixelFormat::L8 => pixels.iter().flat_map(|&l| [l, l, l, 255]).collect(),
+            // 16 bit samples are big endian, keep the most significant byte
+            PixelFormat::L16 => pixels.chunks_exact(2).flat_map(|l| [l[0], l[0], l[0], 255]).collect(),
+            PixelFormat::RGB24 => pixels.chunks_exact(3).flat_map(|rgb| [rgb[0], rgb[1], rgb[2], 255]).collect(),
+            PixelFormat::CMYK32 => return Err(Error::UnsupportedImageFormat("CMYK JPEG images are not supported")),
+        };
+
+        Ok(Image {
+            width: info.width,
+            height: info.height,
+            bytes,
+        })
+    }
+
+    #[cfg(feature = "webp")]
+    fn decode_webp(bytes: &[u8]) -> Result<Image, Error> {
+        let mut decoder = image_webp::WebPDecoder::new(Cursor::new(bytes))?;
+        let (width, height) = decoder.dimensions();
+        let has_alpha = decoder.has_alpha();
+
+        let mut pixels = vec![0; decoder.output_buffer_size().expect("Should have output size")];
+        decoder.read_image(&mut pixels)?;
+
+        let bytes = if has_alpha {
+            pixels
+        } else {
+            pixels.chunks_exact(3).flat_map(|rgb| [rgb[0], rgb[1], rgb[2], 255]).collect()
+        };
+
+        Ok(Image {
+            width: width as u16,
+            height: height as u16,
+            bytes,
+        })
+    }
+
+    /// Multiplies the color channels of each pixel by its alpha.
+    ///
+    /// Premultiplied images avoid the dark or light fringes that appear around transparent
+    /// edges with linear filtering, but have to be drawn with premultiplied alpha blending,
+    /// see `gl_use_premultiplied_alpha_material`.
+    pub fn premultiply_alpha(&mut self) {
+        for pixel in self.bytes.chunks_exact_mut(4) {
+            let alpha = pixel[3] as u16;
+            for channel in &mut pixel[..3] {
+                *channel = ((*channel as u16 * alpha + 127) / 255) as u8;
+            }
+        }
+    }
+
+    /// Replaces every pixel exactly matching one of the `from` colors of `map` with its `to` color,
+    /// e.g. to derive team color variants from a single pixel art sprite.
+    ///
+    /// Colors are compared after conversion to 8 bits per channel, alpha included.
+    pub fn recolor(&mut self, map: &[(Color, Color)]) {
+        let map = map
+            .iter()
+            .map(|&(from, to)| (<[u8; 4]>::from(from), <[u8; 4]>::from(to)))
+            .collect::<Vec<_>>();
+
+        for pixel in self.bytes.chunks_exact_mut(4) {
+            if let Some((_, to)) = map.iter().find(|(from, _)| pixel == from) {
+                pixel.copy_from_slice(to);
+            }
+        }
+    }
+
     /// Creates an Image filled with the provided [Color].
     pub fn gen_image_color(width: u16, height: u16, color: Color) -> Image {
         let mut bytes = vec![0; width as usize * height as usize * 4];
@@ -368,6 +463,7 @@ impl Drop for RenderPass {
 pub struct RenderTarget {
     pub texture: Texture2D,
     pub render_pass: RenderPass,
+    params: RenderTargetParams,
 }
 
 impl RenderTarget {
@@ -381,6 +477,38 @@ impl RenderTarget {
         let ctx = get_quad_context();
         ctx.texture_read_pixels(self.texture.raw_miniquad_id(), buf);
     }
+
+    /// Clear this render target to `color`, and its depth and stencil attachments
+    /// when `depth`/`stencil` are given, regardless of the active camera.
+    ///
+    /// Draw calls made so far are flushed first, so they are not affected by the clear.
+    pub fn clear(&self, color: Color, depth: Option<f32>, stencil: Option<i32>) {
+        get_context().perform_render_passes();
+
+        let ctx = get_quad_context();
+        ctx.begin_pass(
+            Some(self.render_pass.raw_miniquad_id()),
+            miniquad::PassAction::Clear {
+                color: Some((color.r, color.g, color.b, color.a)),
+                depth,
+                stencil,
+            },
+        );
+        ctx.end_render_pass();
+    }
+
+    /// Reallocate this render target at `width` x `height`, keeping its multi sampling and depth settings,
+    /// e.g. from `window::on_resize` for post-processing done at the screen resolution.
+    ///
+    /// The content is lost. Clones made before, like the one given to a `Camera2D`, and `texture` handles
+    /// keep pointing to the old target, so they have to be taken again from this one.
+    /// The old target is freed once none of them is left.
+    pub fn resize(&mut self, width: u32, height: u32) {
+        if self.texture.width() as u32 == width && self.texture.height() as u32 == height {
+            return;
+        }
+        *self = render_target_ex(width, height, self.params.clone());
+    }
 }
 
 /// A shortcut to create a render target with sample_count: 1 and no depth buffer
@@ -442,7 +570,11 @@ pub fn render_target_ex(width: u32, height: u32, params: RenderTargetParams) ->
         depth_texture: None,
         render_pass: Arc::new(render_pass),
     };
-    RenderTarget { texture, render_pass }
+    RenderTarget {
+        texture,
+        render_pass,
+        params,
+    }
 }
 
 #[derive(Debug, Clone)]
@@ -469,6 +601,17 @@ pub struct DrawTextureParams {
     /// E.g. pivot (0,0) rotates around the top left corner of the screen, not of the
     /// texture.
     pub pivot: Option<Vec2>,
+
+    /// Transform applied to the corners last, after `dest_size`, flips and `rotation`,
+    /// for shear, non-uniform scaling or mirroring around an arbitrary axis.
+    /// Like `pivot`, it works in screen-space: to skew around the texture's center,
+    /// translate the center to the origin and back around the skew.
+    pub transform: Option<Affine2>,
+
+    /// Color of each corner of the texture, in place of the `color` argument, blended across the quad,
+    /// e.g. to fade a sprite out toward one edge.
+    /// Clockwise from the top left one, they stay at the texture corners with flips and rotation.
+    pub corner_colors: Option<[Color; 4]>,
 }
 
 impl Default for DrawTextureParams {
@@ -480,6 +623,8 @@ impl Default for DrawTextureParams {
             pivot: None,
             flip_x: false,
             flip_y: false,
+            transform: None,
+            corner_colors: None,
         }
     }
 }
@@ -555,13 +700,18 @@ pub fn draw_texture_ex(texture: &Texture2D, x: f32, y: f32, color: Color, params
         vec2(p[2].x * r_cos - p[2].y * r_sin, p[2].x * r_sin + p[2].y * r_cos) + m,
         vec2(p[3].x * r_cos - p[3].y * r_sin, p[3].x * r_sin + p[3].y * r_cos) + m,
     ];
+    let p = match params.transform {
+        Some(transform) => p.map(|corner| transform.transform_point2(corner)),
+        None => p,
+    };
+    let c = params.corner_colors.unwrap_or([color; 4]);
     let z = context.gl.draw_depth();
     #[rustfmt::skip]
     let vertices = [
-        Vertex::new(p[0].x, p[0].y, z,  sx      /width,  sy      /height, color),
-        Vertex::new(p[1].x, p[1].y, z, (sx + sw)/width,  sy      /height, color),
-        Vertex::new(p[2].x, p[2].y, z, (sx + sw)/width, (sy + sh)/height, color),
-        Vertex::new(p[3].x, p[3].y, z,  sx      /width, (sy + sh)/height, color),
+        Vertex::new(p[0].x, p[0].y, z,  sx      /width,  sy      /height, c[0]),
+        Vertex::new(p[1].x, p[1].y, z, (sx + sw)/width,  sy      /height, c[1]),
+        Vertex::new(p[2].x, p[2].y, z, (sx + sw)/width, (sy + sh)/height, c[2]),
+        Vertex::new(p[3].x, p[3].y, z,  sx      /width, (sy + sh)/height, c[3]),
     ];
     let indices: [u16; 6] = [0, 1, 2, 0, 2, 3];
 
@@ -570,6 +720,140 @@ pub fn draw_texture_ex(texture: &Texture2D, x: f32, y: f32, color: Color, params
     context.gl.geometry(&vertices, &indices);
 }
 
+/// How `draw_texture_fit` places a texture within its bounds.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FitMode {
+    /// Fill the bounds, ignoring the texture aspect ratio.
+    Stretch,
+    /// Show the whole texture as large as it fits, letterboxed on two sides.
+    Contain,
+    /// Fill the bounds, cropping the texture on two sides.
+    Cover,
+}
+
+/// Draw `texture` within `bounds` keeping its aspect ratio, centered, as `mode` says.
+pub fn draw_texture_fit(texture: &Texture2D, bounds: Rect, mode: FitMode, color: Color) {
+    let (dest, source) = fit_rects(texture.size(), bounds, mode);
+
+    draw_texture_ex(
+        texture,
+        dest.x,
+        dest.y,
+        color,
+        DrawTextureParams {
+            dest_size: Some(dest.size()),
+            source: Some(source),
+            ..Default::default()
+        },
+    );
+}
+
+/// Destination rect within `bounds` and source rect within the texture for `draw_texture_fit`.
+fn fit_rects(texture_size: Vec2, bounds: Rect, mode: FitMode) -> (Rect, Rect) {
+    let whole = Rect::new(0., 0., texture_size.x, texture_size.y);
+    let scale_x = bounds.w / texture_size.x;
+    let scale_y = bounds.h / texture_size.y;
+
+    match mode {
+        FitMode::Stretch => (bounds, whole),
+        FitMode::Contain => {
+            let size = texture_size * scale_x.min(scale_y);
+            let offset = (bounds.size() - size) / 2.;
+            (Rect::new(bounds.x + offset.x, bounds.y + offset.y, size.x, size.y), whole)
+        }
+        FitMode::Cover => {
+            let visible = bounds.size() / scale_x.max(scale_y);
+            let offset = (texture_size - visible) / 2.;
+            (bounds, Rect::new(offset.x, offset.y, visible.x, visible.y))
+        }
+    }
+}
+
+#[test]
+fn fit_rects_keep_aspect() {
+    let bounds = Rect::new(10., 20., 200., 100.);
+
+    // landscape bounds, portrait texture
+    let (dest, source) = fit_rects(Vec2::new(50., 100.), bounds, FitMode::Contain);
+    assert_eq!(dest, Rect::new(85., 20., 50., 100.));
+    assert_eq!(source, Rect::new(0., 0., 50., 100.));
+
+    let (dest, source) = fit_rects(Vec2::new(50., 100.), bounds, FitMode::Cover);
+    assert_eq!(dest, bounds);
+    assert_eq!(source, Rect::new(0., 37.5, 50., 25.));
+
+    // texture wider than the bounds
+    let (dest, source) = fit_rects(Vec2::new(400., 100.), bounds, FitMode::Cover);
+    assert_eq!(dest, bounds);
+    assert_eq!(source, Rect::new(100., 0., 200., 100.));
+
+    let (dest, _) = fit_rects(Vec2::new(400., 100.), bounds, FitMode::Stretch);
+    assert_eq!(dest, bounds);
+}
+
+/// Draw `texture` mapped onto four arbitrary points, for skews, trapezoids and other warps.
+///
+/// `corners` and `uvs` go clockwise starting from the top left one,
+/// `uvs` being normalized texture coordinates (`vec2(0., 0.)` to `vec2(1., 1.)` for the whole texture).
+/// The quad is split into two triangles along the `corners[0]` - `corners[2]` diagonal,
+/// so the mapping is affine within each triangle rather than perspective correct.
+pub fn draw_texture_quad(texture: &Texture2D, corners: [Vec2; 4], uvs: [Vec2; 4], color: Color) {
+    let context = get_context();
+
+    let z = context.gl.draw_depth();
+    let vertices = [0, 1, 2, 3].map(|i| Vertex::new(corners[i].x, corners[i].y, z, uvs[i].x, uvs[i].y, color));
+    let indices: [u16; 6] = [0, 1, 2, 0, 2, 3];
+
+    context.gl.texture(Some(texture));
+    context.gl.draw_mode(DrawMode::Triangles);
+    context.gl.geometry(&vertices, &indices);
+}
+
+/// Draw `texture` stretched over `dest` with its corners rounded by `radius`, e.g. for avatars and thumbnails.
+///
+/// The rounded shape is tessellated with matching texture coordinates, no stencil involved,
+/// and fades out over one unit (a pixel with the default camera) past its edge to stay smooth.
+pub fn draw_texture_rounded(texture: &Texture2D, dest: Rect, radius: f32, color: Color) {
+    const SEGMENTS: usize = 8;
+    const FEATHER: f32 = 1.;
+
+    let context = get_context();
+
+    // clamped here so the feathered outline is the same shape grown by `FEATHER`
+    let radius = radius.clamp(0., dest.w.min(dest.h) / 2.);
+    let inner = crate::shapes::rounded_rect_points(dest, radius, SEGMENTS);
+    let outer = crate::shapes::rounded_rect_points(
+        Rect::new(dest.x - FEATHER, dest.y - FEATHER, dest.w + FEATHER * 2., dest.h + FEATHER * 2.),
+        radius + FEATHER,
+        SEGMENTS,
+    );
+
+    let z = context.gl.draw_depth();
+    let uv = |point: Vec2| (point - dest.point()) / dest.size();
+    let transparent = Color { a: 0., ..color };
+    let center = dest.center();
+
+    let mut vertices = Vec::with_capacity(1 + inner.len() * 2);
+    vertices.push(Vertex::new(center.x, center.y, z, 0.5, 0.5, color));
+    vertices.extend(inner.iter().map(|p| Vertex::new(p.x, p.y, z, uv(*p).x, uv(*p).y, color)));
+    // the fading ring samples the texture edge rather than outside of it
+    for (p, edge) in outer.iter().zip(&inner) {
+        vertices.push(Vertex::new(p.x, p.y, z, uv(*edge).x, uv(*edge).y, transparent));
+    }
+
+    let count = inner.len() as u16;
+    let mut indices = Vec::with_capacity(inner.len() * 9);
+    for i in 0..count {
+        let next = (i + 1) % count;
+        indices.extend_from_slice(&[0, 1 + i, 1 + next]);
+        indices.extend_from_slice(&[1 + i, 1 + next, 1 + count + next, 1 + i, 1 + count + next, 1 + count + i]);
+    }
+
+    context.gl.texture(Some(texture));
+    context.gl.draw_mode(DrawMode::Triangles);
+    context.gl.geometry(&vertices, &indices);
+}
+
 /// Get pixel data from screen buffer and return an Image (screenshot)
 pub fn get_screen_data() -> Image {
     unsafe {
@@ -591,6 +875,166 @@ pub fn get_screen_data() -> Image {
     texture.get_texture_data()
 }
 
+/// Settings of [start_frame_capture].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct FrameCaptureConf {
+    /// Capture one frame out of `stride`, 1 captures every frame.
+    pub stride: u32,
+    /// Frames larger than this (width, height) are scaled down to fit, keeping their aspect ratio.
+    pub max_size: Option<(u32, u32)>,
+}
+
+impl Default for FrameCaptureConf {
+    fn default() -> FrameCaptureConf {
+        FrameCaptureConf { stride: 1, max_size: None }
+    }
+}
+
+pub(crate) struct FrameCapture {
+    dir: std::path::PathBuf,
+    conf: FrameCaptureConf,
+    /// Frames to skip before the next capture.
+    skipped_frames: u32,
+    frames_written: u64,
+}
+
+impl FrameCapture {
+    /// Called by `Context::end_frame` once the frame is fully drawn.
+    pub(crate) fn capture_frame(&mut self) {
+        if self.skipped_frames > 0 {
+            self.skipped_frames -= 1;
+            return;
+        }
+        self.skipped_frames = self.conf.stride.max(1) - 1;
+
+        let mut image = get_screen_data();
+        if let Some(max_size) = self.conf.max_size {
+            image = scale_down(image, max_size);
+        }
+
+        let path = self.dir.join(format!("frame_{:05}.png", self.frames_written));
+        image.export_png(&path.to_string_lossy());
+        self.frames_written += 1;
+    }
+}
+
+/// Nearest neighbour downscale of `image` to fit in `max_size`, as is if it already fits.
+fn scale_down(image: Image, max_size: (u32, u32)) -> Image {
+    let scale = (max_size.0 as f32 / image.width as f32).min(max_size.1 as f32 / image.height as f32);
+    if scale >= 1. {
+        return image;
+    }
+
+    let width = ((image.width as f32 * scale) as usize).max(1);
+    let height = ((image.height as f32 * scale) as usize).max(1);
+    let mut bytes = Vec::with_capacity(width * height * 4);
+    for y in 0..height {
+        let source_y = y * image.height as usize / height;
+        for x in 0..width {
+            let i = (source_y * image.width as usize + x * image.width as usize / width) * 4;
+            bytes.extend_from_slice(&image.bytes[i..i + 4]);
+        }
+    }
+
+    Image {
+        bytes,
+        width: width as u16,
+        height: height as u16,
+    }
+}
+
+/// Save every frame drawn from now on as numbered PNG files (`frame_00000.png`, ...) in `dir`,
+/// until [stop_frame_capture], e.g. for trailers or bug reports.
+///
+/// Each captured frame is read back from the GPU and encoded before the next one starts,
+/// so expect the frame rate to drop: `conf` can skip frames and cap the resolution.
+/// Files are written with `std::fs`, so capturing is not supported on web, where it only logs a warning.
+pub fn start_frame_capture(dir: &str, conf: FrameCaptureConf) {
+    if cfg!(target_arch = "wasm32") {
+        warn!("start_frame_capture is not supported on this platform");
+        return;
+    }
+    if let Err(err) = std::fs::create_dir_all(dir) {
+        warn!("start_frame_capture: can't create {}: {}", dir, err);
+        return;
+    }
+
+    get_context().frame_capture = Some(FrameCapture {
+        dir: dir.into(),
+        conf,
+        skipped_frames: 0,
+        frames_written: 0,
+    });
+}
+
+/// Stop the capture started by [start_frame_capture], the current frame is not captured.
+pub fn stop_frame_capture() {
+    get_context().frame_capture = None;
+}
+
+/// Returns the color of the pixel at `x`, `y` in logical pixels from the top left corner of the window,
+/// as drawn so far this frame, e.g. for an eyedropper tool.
+///
+/// Pending draw calls are flushed and the CPU waits for the GPU to finish them, so this is
+/// a full GPU sync: fine once per frame, costly in a loop.
+/// With `set_render_scale`, the pixel is read from the scaled frame.
+/// Positions outside of the window are clamped to its edges.
+///
+/// Returns `None` with backends other than OpenGL.
+#[allow(unreachable_patterns)]
+pub fn get_pixel(x: f32, y: f32) -> Option<Color> {
+    use miniquad::gl::*;
+
+    if get_quad_context().info().backend != miniquad::Backend::OpenGl {
+        return None;
+    }
+
+    get_context().perform_render_passes();
+
+    let context = get_context();
+    let dpi = miniquad::window::dpi_scale();
+    let to_physical = |value: f32, scale: f32, size: u32| ((value * dpi * scale) as i32).clamp(0, size as i32 - 1);
+
+    let mut pixel = [0u8; 4];
+    // framebuffers are read bottom row first
+    let read_pixel = |x: i32, y: i32, pixel: &mut [u8; 4]| unsafe {
+        glReadPixels(x, y, 1, 1, GL_RGBA, GL_UNSIGNED_BYTE, pixel.as_mut_ptr() as _);
+    };
+
+    if let Some(target) = &context.render_scale_target {
+        let (width, height) = (target.texture.width() as u32, target.texture.height() as u32);
+        let scale = context.render_scale;
+        let (x, y) = (to_physical(x, scale, width), to_physical(y, scale, height));
+
+        let raw = match unsafe { get_quad_context().texture_raw_id(target.texture.raw_miniquad_id()) } {
+            miniquad::RawId::OpenGl(raw) => raw,
+            _ => return None,
+        };
+
+        // a framebuffer over the target's texture, to read only the one pixel from it
+        unsafe {
+            let mut bound = 0;
+            glGetIntegerv(GL_FRAMEBUFFER_BINDING, &mut bound);
+            let mut framebuffer = 0;
+            glGenFramebuffers(1, &mut framebuffer);
+            glBindFramebuffer(GL_FRAMEBUFFER, framebuffer);
+            glFramebufferTexture2D(GL_FRAMEBUFFER, GL_COLOR_ATTACHMENT0, GL_TEXTURE_2D, raw, 0);
+
+            read_pixel(x, height as i32 - 1 - y, &mut pixel);
+
+            glBindFramebuffer(GL_FRAMEBUFFER, bound as _);
+            glDeleteFramebuffers(1, &framebuffer);
+        }
+    } else {
+        let (width, height) = (context.screen_width as u32, context.screen_height as u32);
+        let (x, y) = (to_physical(x, 1., width), to_physical(y, 1., height));
+
+        read_pixel(x, height as i32 - 1 - y, &mut pixel);
+    }
+
+    Some(pixel.into())
+}
+
 /// Texture, data stored in GPU memory
 #[derive(Clone, Debug, PartialEq)]
 pub struct Texture2D {
@@ -633,6 +1077,16 @@ impl Texture2D {
             TextureHandle::ManagedWeak(t) => Texture2D::create_and_cache_size(TextureHandle::ManagedWeak(*t)),
         }
     }
+    /// A texture not backed by any GPU texture, for tests running without a graphics context.
+    #[cfg(test)]
+    pub(crate) fn detached() -> Texture2D {
+        Texture2D {
+            texture: TextureHandle::Unmanaged(miniquad::TextureId::from_raw_id(miniquad::RawId::OpenGl(0))),
+            width: 0.,
+            height: 0.,
+        }
+    }
+
     pub(crate) fn unmanaged(texture: miniquad::TextureId) -> Texture2D {
         Texture2D::create_and_cache_size(TextureHandle::Unmanaged(texture))
     }
@@ -654,8 +1108,7 @@ impl Texture2D {
 
     /// Creates a Texture2D from a slice of bytes that contains an encoded image.
     ///
-    /// If `format` is None, it will make an educated guess on the
-    /// [ImageFormat][image::ImageFormat].
+    /// Supports the same formats as [Image::from_file_with_format] and panics if decoding fails.
     ///
     /// # Example
     /// ```
@@ -669,19 +1122,9 @@ impl Texture2D {
     /// # }
     /// ```
     pub fn from_file_with_format(bytes: &[u8]) -> Texture2D {
-        let mut decoder = Decoder::new(Cursor::new(bytes));
-        decoder.set_transformations(png::Transformations::EXPAND | png::Transformations::ALPHA);
-        let mut reader = decoder.read_info().expect("Failed to read image info");
-
-        let output_size = reader.output_buffer_size().expect("Should have output size");
-        let mut buf = vec![0; output_size];
-        let info = reader.next_frame(&mut buf).expect("Failed to read image frame");
+        let image = Image::from_file_with_format(bytes).expect("Failed to decode image");
 
-        let width = info.width as u16;
-        let height = info.height as u16;
-        let bytes = buf[..info.buffer_size()].to_vec();
-
-        Self::from_rgba8(width, height, &bytes)
+        Self::from_image(&image)
     }
 
     /// Creates a Texture2D from an [Image].
@@ -689,6 +1132,19 @@ impl Texture2D {
         Texture2D::from_rgba8(image.width, image.height, &image.bytes)
     }
 
+    /// Creates a Texture2D from an [Image], premultiplying its alpha on upload.
+    ///
+    /// Transparent edges of such textures blend correctly when filtered, but they have to be drawn
+    /// with premultiplied alpha blending (see `gl_use_premultiplied_alpha_material`),
+    /// and reading them back gives premultiplied colors.
+    /// [Texture2D::from_image] keeps the straight alpha and remains the default.
+    pub fn from_image_premultiplied(image: &Image) -> Texture2D {
+        let mut image = image.clone();
+        image.premultiply_alpha();
+
+        Texture2D::from_image(&image)
+    }
+
     /// Creates a Texture2D from a miniquad
     /// [Texture](https://docs.rs/miniquad/0.3.0-alpha/miniquad/graphics/struct.Texture.html)
     pub fn from_miniquad_texture(texture: miniquad::TextureId) -> Texture2D {
@@ -786,6 +1242,21 @@ impl Texture2D {
         ctx.raw_miniquad_id(&self.texture)
     }
 
+    /// Returns whether the GPU texture is available. When it is not, drawing this texture
+    /// draws the white fallback texture instead.
+    ///
+    /// Textures are uploaded when created, so this is only false for a weak clone
+    /// (see [Texture2D::weak_clone]) of a texture that was already freed.
+    pub fn is_loaded(&self) -> bool {
+        let ctx = get_context();
+
+        match &self.texture {
+            TextureHandle::Unmanaged(_) => true,
+            TextureHandle::Managed(texture) => ctx.textures.texture(texture.0).is_some(),
+            TextureHandle::ManagedWeak(texture) => ctx.textures.texture(*texture).is_some(),
+        }
+    }
+
     /// Updates this texture from the screen.
     #[allow(unreachable_patterns)]
     pub fn grab_screen(&self) {
@@ -838,6 +1309,212 @@ impl Texture2D {
     }
 }
 
+/// A texture split into a list of sub-regions, like a tileset or a sprite sheet.
+#[derive(Clone, Debug)]
+pub struct Atlas {
+    pub texture: Texture2D,
+    /// Source rects in texture pixels, indexed by tile/frame id.
+    pub regions: Vec<Rect>,
+}
+
+impl Atlas {
+    pub fn new(texture: Texture2D, regions: Vec<Rect>) -> Atlas {
+        Atlas { texture, regions }
+    }
+
+    /// Splits the texture into a uniform grid of `tile_width` x `tile_height` cells.
+    /// Cells are numbered row by row, starting at the top-left corner.
+    /// Partial cells on the right and bottom edges are ignored.
+    pub fn from_grid(texture: Texture2D, tile_width: f32, tile_height: f32) -> Atlas {
+        let columns = (texture.width() / tile_width) as usize;
+        let rows = (texture.height() / tile_height) as usize;
+
+        let mut regions = Vec::with_capacity(columns * rows);
+        for row in 0..rows {
+            for column in 0..columns {
+                regions.push(Rect::new(
+                    column as f32 * tile_width,
+                    row as f32 * tile_height,
+                    tile_width,
+                    tile_height,
+                ));
+            }
+        }
+
+        Atlas { texture, regions }
+    }
+
+    /// Decodes a sprite strip or sheet (PNG, or JPEG/WebP with their features) and splits it
+    /// into `frame_width` x `frame_height` frames, like [Atlas::from_grid].
+    pub fn from_sprite_strip(bytes: &[u8], frame_width: f32, frame_height: f32) -> Result<Atlas, Error> {
+        let image = Image::from_file_with_format(bytes)?;
+
+        Ok(Atlas::from_grid(Texture2D::from_image(&image), frame_width, frame_height))
+    }
+
+    /// Decodes an animated GIF into an atlas with one region per frame, and the delay in seconds
+    /// of each frame. Frames are composited like a GIF viewer would, so each region is a full image.
+    ///
+    /// Requires the `gif` feature.
+    #[cfg(feature = "gif")]
+    pub fn from_gif(bytes: &[u8]) -> Result<(Atlas, Vec<f32>), Error> {
+        use gif::DisposalMethod;
+
+        let mut options = gif::DecodeOptions::new();
+        options.set_color_output(gif::ColorOutput::RGBA);
+        let mut decoder = options.read_info(bytes)?;
+        let (width, height) = (decoder.width() as usize, decoder.height() as usize);
+
+        // frames only update a part of the previous one, keep the whole picture in `canvas`
+        let mut canvas = vec![0u8; width * height * 4];
+        let mut frames = vec![];
+        let mut delays = vec![];
+        while let Some(frame) = decoder.read_next_frame()? {
+            let previous = (frame.dispose == DisposalMethod::Previous).then(|| canvas.clone());
+            let (left, top) = (frame.left as usize, frame.top as usize);
+            let frame_width = frame.width as usize;
+            let columns = frame_width.min(width.saturating_sub(left));
+            let rows = (frame.height as usize).min(height.saturating_sub(top));
+
+            for y in 0..rows {
+                for x in 0..columns {
+                    let pixel = &frame.buffer[(y * frame_width + x) * 4..][..4];
+                    if pixel[3] != 0 {
+                        let offset = ((top + y) * width + left + x) * 4;
+                        canvas[offset..offset + 4].copy_from_slice(pixel);
+                    }
+                }
+            }
+            frames.push(canvas.clone());
+            // GIF delays are in hundredths of a second
+            delays.push(frame.delay as f32 / 100.);
+
+            match frame.dispose {
+                DisposalMethod::Background => {
+                    for y in 0..rows {
+                        let offset = ((top + y) * width + left) * 4;
+                        canvas[offset..offset + columns * 4].fill(0);
+                    }
+                }
+                DisposalMethod::Previous => canvas = previous.unwrap(),
+                _ => {}
+            }
+        }
+
+        if frames.is_empty() {
+            return Err(Error::UnsupportedImageFormat("GIF without frames"));
+        }
+
+        // a square-ish sheet keeps the texture within size limits for long animations
+        let columns = (frames.len() as f32).sqrt().ceil() as usize;
+        let rows = frames.len().div_ceil(columns);
+        if width * columns > u16::MAX as usize || height * rows > u16::MAX as usize {
+            return Err(Error::UnsupportedImageFormat("GIF too large to fit its frames in one texture"));
+        }
+
+        let sheet_width = width * columns;
+        let mut bytes = vec![0; sheet_width * height * rows * 4];
+        let mut regions = Vec::with_capacity(frames.len());
+        for (i, frame) in frames.iter().enumerate() {
+            let (x, y) = (i % columns * width, i / columns * height);
+            for row in 0..height {
+                let offset = ((y + row) * sheet_width + x) * 4;
+                bytes[offset..offset + width * 4].copy_from_slice(&frame[row * width * 4..][..width * 4]);
+            }
+            regions.push(Rect::new(x as f32, y as f32, width as f32, height as f32));
+        }
+
+        let image = Image {
+            width: sheet_width as u16,
+            height: (height * rows) as u16,
+            bytes,
+        };
+        Ok((Atlas::new(Texture2D::from_image(&image), regions), delays))
+    }
+
+    /// Returns the source rect of the region with the given index.
+    pub fn region(&self, index: usize) -> Option<Rect> {
+        self.regions.get(index).copied()
+    }
+
+    /// Returns the number of regions in this atlas.
+    pub fn len(&self) -> usize {
+        self.regions.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.regions.is_empty()
+    }
+}
+
+/// Collects `draw_texture_ex` calls and submits them grouped by texture on [SpriteBatch::flush],
+/// so interleaved draws of a few textures end up in a few draw calls.
+///
+/// Draws of the same texture keep their relative order, draws of different textures may be reordered.
+/// Sprites that overlap and must stay in order regardless of their texture can be put in
+/// different layers with [SpriteBatch::draw_layered], lower layers are always drawn first.
+#[derive(Default)]
+pub struct SpriteBatch {
+    sprites: Vec<BatchedSprite>,
+}
+
+struct BatchedSprite {
+    texture: Texture2D,
+    layer: i32,
+    x: f32,
+    y: f32,
+    color: Color,
+    params: DrawTextureParams,
+}
+
+impl SpriteBatch {
+    pub fn new() -> SpriteBatch {
+        SpriteBatch::default()
+    }
+
+    /// Queue a sprite on layer 0, same arguments as `draw_texture_ex`.
+    pub fn draw(&mut self, texture: &Texture2D, x: f32, y: f32, color: Color, params: DrawTextureParams) {
+        self.draw_layered(0, texture, x, y, color, params);
+    }
+
+    /// Queue a sprite on `layer`, overriding the texture grouping for sprites on different layers.
+    pub fn draw_layered(&mut self, layer: i32, texture: &Texture2D, x: f32, y: f32, color: Color, params: DrawTextureParams) {
+        self.sprites.push(BatchedSprite {
+            texture: texture.clone(),
+            layer,
+            x,
+            y,
+            color,
+            params,
+        });
+    }
+
+    /// Number of sprites queued since the last flush.
+    pub fn len(&self) -> usize {
+        self.sprites.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.sprites.is_empty()
+    }
+
+    /// Draw all queued sprites, by layer and then by texture, and empty the batch.
+    pub fn flush(&mut self) {
+        // groups are ordered by their first sprite, the stable sort keeps the order within a group
+        let mut groups = HashMap::new();
+        let mut sprites: Vec<((i32, usize), BatchedSprite)> = Vec::with_capacity(self.sprites.len());
+        for (i, sprite) in self.sprites.drain(..).enumerate() {
+            let group = *groups.entry((sprite.layer, sprite.texture.raw_miniquad_id())).or_insert(i);
+            sprites.push(((sprite.layer, group), sprite));
+        }
+        sprites.sort_by_key(|(key, _)| *key);
+
+        for (_, sprite) in sprites {
+            draw_texture_ex(&sprite.texture, sprite.x, sprite.y, sprite.color, sprite.params);
+        }
+    }
+}
+
 pub(crate) struct Batcher {
     unbatched: Vec<Texture2D>,
 }
@@ -852,8 +1529,17 @@ impl Batcher {
     }
 }
 
+/// Set the filter mode of textures created from now on, initially `Conf::default_filter_mode`.
+///
+/// Textures that already exist keep their filter, use [Texture2D::set_filter] to change them.
+/// Switching between `Nearest` for pixel art and `Linear` for smooth UI can be done at any time.
 pub fn set_default_filter_mode(filter: FilterMode) {
     let context = get_context();
 
     context.default_filter_mode = filter;
 }
+
+/// Returns the filter mode given to newly created textures, see [set_default_filter_mode].
+pub fn default_filter_mode() -> FilterMode {
+    get_context().default_filter_mode
+}
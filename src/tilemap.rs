@@ -0,0 +1,83 @@
+//! Batched drawing of 2D tile grids.
+
+use crate::{
+    color::WHITE,
+    get_context,
+    math::Vec2,
+    quad_gl::{DrawMode, Vertex},
+    texture::Atlas,
+    window::screen_rect,
+};
+
+/// Tile index that is never drawn by [draw_tilemap].
+pub const EMPTY_TILE: u32 = u32::MAX;
+
+/// Draws a `cols` x `rows` grid of tiles from `tileset`, with the top-left corner of the grid at `origin`.
+///
+/// `tiles` are stored row by row, each value being an index into `tileset.regions`.
+/// Tiles equal to [EMPTY_TILE] or pointing outside of the atlas are skipped,
+/// and so are the rows missing when `tiles` is shorter than `cols * rows`.
+///
+/// Only tiles intersecting the area visible through the active camera are submitted,
+/// and all of them go through the batcher as a few large geometry chunks.
+pub fn draw_tilemap(tiles: &[u32], cols: usize, rows: usize, tileset: &Atlas, origin: Vec2, tile_size: Vec2) {
+    // Keeps each chunk well below the default draw call capacity (10000 vertices, 5000 indices).
+    const TILES_PER_CHUNK: usize = 512;
+
+    if cols == 0 || tile_size.x <= 0. || tile_size.y <= 0. {
+        return;
+    }
+    let rows = rows.min(tiles.len() / cols);
+
+    let visible = screen_rect();
+    let first_col = ((visible.left() - origin.x) / tile_size.x).floor().max(0.) as usize;
+    let last_col = (((visible.right() - origin.x) / tile_size.x).ceil().max(0.) as usize).min(cols);
+    let first_row = ((visible.top() - origin.y) / tile_size.y).floor().max(0.) as usize;
+    let last_row = (((visible.bottom() - origin.y) / tile_size.y).ceil().max(0.) as usize).min(rows);
+
+    let context = get_context();
+    let z = context.gl.draw_depth();
+    let texture_size = tileset.texture.size();
+
+    context.gl.texture(Some(&tileset.texture));
+    context.gl.draw_mode(DrawMode::Triangles);
+
+    let mut vertices = Vec::<Vertex>::with_capacity(TILES_PER_CHUNK * 4);
+    let mut indices = Vec::<u16>::with_capacity(TILES_PER_CHUNK * 6);
+
+    for row in first_row..last_row {
+        for col in first_col..last_col {
+            let tile = tiles[row * cols + col];
+            if tile == EMPTY_TILE {
+                continue;
+            }
+            let Some(source) = tileset.region(tile as usize) else {
+                continue;
+            };
+
+            let x = origin.x + col as f32 * tile_size.x;
+            let y = origin.y + row as f32 * tile_size.y;
+            let (u0, v0) = (source.x / texture_size.x, source.y / texture_size.y);
+            let (u1, v1) = (source.right() / texture_size.x, source.bottom() / texture_size.y);
+
+            indices.extend([0, 1, 2, 0, 2, 3].map(|k| k + vertices.len() as u16));
+            #[rustfmt::skip]
+            vertices.extend([
+                Vertex::new(x              , y              , z, u0, v0, WHITE),
+                Vertex::new(x + tile_size.x, y              , z, u1, v0, WHITE),
+                Vertex::new(x + tile_size.x, y + tile_size.y, z, u1, v1, WHITE),
+                Vertex::new(x              , y + tile_size.y, z, u0, v1, WHITE),
+            ]);
+
+            if vertices.len() >= TILES_PER_CHUNK * 4 {
+                context.gl.geometry(&vertices, &indices);
+                vertices.clear();
+                indices.clear();
+            }
+        }
+    }
+
+    if !vertices.is_empty() {
+        context.gl.geometry(&vertices, &indices);
+    }
+}
@@ -8,11 +8,39 @@ pub fn get_fps() -> i32 {
     (1. / context.frame_time) as i32
 }
 
-/// Returns duration in seconds of the last frame drawn
+/// Returns duration in seconds of the last frame drawn, multiplied by the time scale
 pub fn get_frame_time() -> f32 {
     let context = get_context();
 
-    context.frame_time as f32
+    (context.frame_time * context.time_scale) as f32
+}
+
+/// Returns a low-pass filtered duration of the last frames, multiplied by the time scale.
+///
+/// Unlike `get_frame_time` it doesn't jitter from frame to frame,
+/// which makes it better suited for camera follow and animations.
+pub fn get_smooth_frame_time() -> f32 {
+    let context = get_context();
+
+    (context.smooth_frame_time * context.time_scale) as f32
+}
+
+/// Scale the frame time reported by `get_frame_time` (and `next_frame_timed`),
+/// e.g. 0.5 for slow motion or 2.0 to speed things up. 0.0 freezes time while
+/// frames keep being drawn.
+///
+/// `get_fps` and `get_time` keep reporting real time.
+pub fn set_time_scale(scale: f32) {
+    let context = get_context();
+
+    context.time_scale = scale.max(0.) as f64;
+}
+
+/// Returns the current time scale, 1.0 by default.
+pub fn get_time_scale() -> f32 {
+    let context = get_context();
+
+    context.time_scale as f32
 }
 
 /// Returns elapsed wall-clock time in seconds since start
@@ -23,7 +51,193 @@ pub fn get_frame_time() -> f32 {
 /// for all game objects, you should call this function once
 /// save the value and reuse it throughout your code.
 pub fn get_time() -> f64 {
+    get_context().clock.time()
+}
+
+/// The clock behind `get_time`, the frame times and the shader `_Time` uniform.
+pub(crate) struct Clock {
+    start_time: f64,
+    #[cfg(any(test, debug_assertions))]
+    source: Option<Box<dyn Fn() -> f64>>,
+}
+
+impl Clock {
+    pub(crate) fn new() -> Clock {
+        Clock {
+            start_time: miniquad::date::now(),
+            #[cfg(any(test, debug_assertions))]
+            source: None,
+        }
+    }
+
+    /// Current time in seconds, from `set_time_source` if set.
+    pub(crate) fn now(&self) -> f64 {
+        #[cfg(any(test, debug_assertions))]
+        if let Some(source) = &self.source {
+            return source();
+        }
+
+        miniquad::date::now()
+    }
+
+    fn time(&self) -> f64 {
+        self.now() - self.start_time
+    }
+
+    fn elapsed_since(&self, timestamp: Timestamp) -> f32 {
+        (self.time() - timestamp.0) as f32
+    }
+
+    /// Switches to `source`, with `time` continuing from its current value.
+    #[cfg(any(test, debug_assertions))]
+    fn set_source(&mut self, source: Option<Box<dyn Fn() -> f64>>) {
+        let time = self.time();
+        self.source = source;
+        self.start_time = self.now() - time;
+    }
+}
+
+/// A point in time captured with `now`, to measure how long ago something happened,
+/// e.g. the time since the last shot, without storing and subtracting `get_time` values by hand.
+#[derive(Debug, Clone, Copy, PartialEq, PartialOrd)]
+pub struct Timestamp(f64);
+
+impl Timestamp {
+    /// Seconds elapsed since this timestamp was captured, see `elapsed_since`.
+    pub fn elapsed(self) -> f32 {
+        elapsed_since(self)
+    }
+}
+
+/// Captures the current time, on the same clock as `get_time`.
+pub fn now() -> Timestamp {
+    Timestamp(get_time())
+}
+
+/// Returns the seconds elapsed since `timestamp` was captured with `now`.
+///
+/// Like `get_time` this is real time: it doesn't depend on the frame rate and
+/// is not affected by `set_time_scale`. It stays continuous across `set_time_source`.
+pub fn elapsed_since(timestamp: Timestamp) -> f32 {
+    get_context().clock.elapsed_since(timestamp)
+}
+
+/// Replace the clock driving `get_time`, frame times and the shader `_Time` uniform,
+/// e.g. with a manually advanced one so tests of timers and animations are deterministic.
+///
+/// `get_time` continues from its current value and the next frame time is measured from now on.
+///
+/// Only available in debug builds, release builds always run on the system clock.
+/// ```ignore
+/// let clock = Rc::new(Cell::new(0.0));
+/// set_time_source({
+///     let clock = clock.clone();
+///     move || clock.get()
+/// });
+/// clock.set(clock.get() + 1. / 60.);
+/// ```
+#[cfg(any(test, debug_assertions))]
+pub fn set_time_source(source: impl Fn() -> f64 + 'static) {
+    replace_time_source(Some(Box::new(source)));
+}
+
+/// Go back to the system clock after `set_time_source`.
+#[cfg(any(test, debug_assertions))]
+pub fn reset_time_source() {
+    replace_time_source(None);
+}
+
+#[cfg(any(test, debug_assertions))]
+fn replace_time_source(source: Option<Box<dyn Fn() -> f64>>) {
+    let context = get_context();
+
+    context.clock.set_source(source);
+    context.last_frame_time = context.clock.now();
+}
+
+#[test]
+fn manual_time_source() {
+    use std::{cell::Cell, rc::Rc};
+
+    let manual = Rc::new(Cell::new(100.));
+    let mut clock = Clock::new();
+    clock.set_source(Some(Box::new({
+        let manual = manual.clone();
+        move || manual.get()
+    })));
+
+    // continues from the system clock time, a moment after `Clock::new`
+    let start = clock.time();
+    assert!((0. ..0.5).contains(&start), "{start}");
+
+    manual.set(101.5);
+    assert!((clock.time() - (start + 1.5)).abs() < 1e-6);
+
+    let timestamp = Timestamp(clock.time());
+    manual.set(101.75);
+    assert!((clock.elapsed_since(timestamp) - 0.25).abs() < 1e-6);
+
+    // back on the system clock, still continuing from the manual time
+    clock.set_source(None);
+    assert!((clock.time() - (start + 1.75)).abs() < 0.5);
+    assert!(clock.elapsed_since(timestamp) >= 0.25);
+}
+
+/// Set the interval in seconds of fixed updates counted by `fixed_update_steps`, 1/60 by default.
+pub fn set_fixed_timestep(step: f32) {
     let context = get_context();
 
-    miniquad::date::now() - context.start_time
+    assert!(step > 0., "fixed timestep must be positive");
+    context.fixed_timestep = step as f64;
+}
+
+/// Returns how many fixed updates should run this frame, consuming them from the
+/// accumulated (time scaled) frame time.
+///
+/// To avoid a spiral of death after a long hitch, at most 8 steps are reported per frame
+/// and the remaining lag is dropped.
+/// ```ignore
+/// for _ in 0..fixed_update_steps() {
+///     world.update(1. / 60.);
+/// }
+/// world.draw(render_alpha());
+/// ```
+pub fn fixed_update_steps() -> u32 {
+    const MAX_STEPS: u32 = 8;
+
+    let context = get_context();
+
+    let steps = (context.fixed_accumulator / context.fixed_timestep) as u32;
+    if steps > MAX_STEPS {
+        context.fixed_accumulator %= context.fixed_timestep;
+        return MAX_STEPS;
+    }
+    context.fixed_accumulator -= steps as f64 * context.fixed_timestep;
+    steps
+}
+
+/// Returns how far into the current fixed timestep interval we are, in the [0, 1] range.
+///
+/// Used to interpolate rendering between the previous and current fixed update states,
+/// for smooth motion regardless of the frame rate. Works without `fixed_update_steps` too,
+/// as the position within the current interval of the fixed timestep.
+pub fn render_alpha() -> f32 {
+    let context = get_context();
+
+    (context.fixed_accumulator % context.fixed_timestep / context.fixed_timestep) as f32
+}
+
+/// Returns the number of frames completed so far, 0 while the first frame is being drawn.
+pub fn get_frame_count() -> u64 {
+    get_context().frame_count
+}
+
+/// Returns true while the first frame is being drawn.
+///
+/// Before any user code runs, `screen_width` and `screen_height` are read again from the window,
+/// so resizes that happened during startup are accounted for. `get_frame_time` reports the
+/// nominal 1/60 during the first frame, the second frame measures time from the first one's start,
+/// not including the startup time. Use it to skip game logic on frame zero.
+pub fn is_first_frame() -> bool {
+    get_context().frame_count == 0
 }
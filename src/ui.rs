@@ -0,0 +1,255 @@
+//! Minimal stateless immediate mode widgets for simple menus, drawn with shapes and text.
+//!
+//! Widgets use the mouse position in logical pixels, so they expect the default camera.
+
+use std::ops::RangeInclusive;
+
+use crate::{
+    color::{Color, WHITE},
+    get_context,
+    input::MouseButton,
+    math::{vec2, Rect, Vec2},
+    shapes::{draw_rectangle, draw_rectangle_lines},
+    text::{draw_text_ex, measure_text, TextParams},
+};
+
+const BACKGROUND: Color = Color::new(0.2, 0.2, 0.2, 0.9);
+const HOVERED: Color = Color::new(0.3, 0.3, 0.3, 0.9);
+const ACTIVE: Color = Color::new(0.4, 0.4, 0.4, 0.9);
+const ACCENT: Color = Color::new(0.00, 0.47, 0.95, 1.00);
+const BORDER: Color = Color::new(0.6, 0.6, 0.6, 1.0);
+
+fn is_hovered(rect: Rect) -> bool {
+    rect.contains(get_context()._mouse_position)
+}
+
+fn is_clicked(rect: Rect) -> bool {
+    is_hovered(rect) && crate::input::read_frame_input().mouse_pressed.contains(&MouseButton::Left)
+}
+
+fn is_held(rect: Rect) -> bool {
+    is_hovered(rect) && get_context().mouse_down.contains(&MouseButton::Left)
+}
+
+/// Draws `text` with its top left corner at `position`.
+pub fn label(position: Vec2, text: &str) {
+    let dimensions = measure_text(text, None, 16, 1.0, None);
+
+    draw_text_ex(
+        text,
+        position.x,
+        position.y + dimensions.offset_y,
+        TextParams {
+            color: WHITE,
+            enable_markup: false,
+            ..Default::default()
+        },
+    );
+}
+
+/// Draws `text` centered in `rect`.
+fn centered_label(rect: Rect, text: &str) {
+    let dimensions = measure_text(text, None, 16, 1.0, None);
+
+    label(
+        vec2(rect.center().x - dimensions.width / 2., rect.center().y - dimensions.height / 2.),
+        text,
+    );
+}
+
+/// Draws a button, returns true on the frame it gets clicked.
+pub fn button(rect: Rect, text: &str) -> bool {
+    let color = if is_held(rect) {
+        ACTIVE
+    } else if is_hovered(rect) {
+        HOVERED
+    } else {
+        BACKGROUND
+    };
+
+    draw_rectangle(rect.x, rect.y, rect.w, rect.h, color);
+    draw_rectangle_lines(rect.x, rect.y, rect.w, rect.h, 1., BORDER);
+    centered_label(rect, text);
+
+    is_clicked(rect)
+}
+
+/// Draws a checkbox as a square box on the left of `rect` followed by `text`.
+/// Clicking anywhere in `rect` toggles `checked`, returns true when it did.
+pub fn checkbox(rect: Rect, text: &str, checked: &mut bool) -> bool {
+    let toggled = is_clicked(rect);
+    if toggled {
+        *checked = !*checked;
+    }
+
+    let check = Rect::new(rect.x, rect.y, rect.h, rect.h);
+    let color = if is_hovered(rect) { HOVERED } else { BACKGROUND };
+    draw_rectangle(check.x, check.y, check.w, check.h, color);
+    draw_rectangle_lines(check.x, check.y, check.w, check.h, 1., BORDER);
+    if *checked {
+        let inset = check.h / 4.;
+        draw_rectangle(check.x + inset, check.y + inset, check.w - inset * 2., check.h - inset * 2., ACCENT);
+    }
+
+    let dimensions = measure_text(text, None, 16, 1.0, None);
+    label(vec2(check.right() + check.h / 3., rect.center().y - dimensions.height / 2.), text);
+
+    toggled
+}
+
+/// Draws a horizontal slider for `value` within `range`, set by clicking or dragging inside `rect`.
+/// Returns true when `value` changed.
+pub fn slider(rect: Rect, value: &mut f32, range: RangeInclusive<f32>) -> bool {
+    let (min, max) = (*range.start(), *range.end());
+
+    let mut changed = false;
+    if is_held(rect) && rect.w > 0. {
+        let t = ((get_context()._mouse_position.x - rect.x) / rect.w).clamp(0., 1.);
+        let new_value = min + (max - min) * t;
+        changed = new_value != *value;
+        *value = new_value;
+    }
+
+    let t = if max > min {
+        ((*value - min) / (max - min)).clamp(0., 1.)
+    } else {
+        0.
+    };
+    let color = if is_hovered(rect) { HOVERED } else { BACKGROUND };
+    draw_rectangle(rect.x, rect.y, rect.w, rect.h, color);
+    draw_rectangle(rect.x, rect.y, rect.w * t, rect.h, ACCENT);
+    draw_rectangle_lines(rect.x, rect.y, rect.w, rect.h, 1., BORDER);
+    centered_label(rect, &format!("{:.2}", *value));
+
+    changed
+}
+
+/// What happened to a [MenuNav] during an update.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MenuAction {
+    /// The selection moved to the new `selected` index.
+    Moved(usize),
+    /// The selected item was activated.
+    Confirm(usize),
+    Cancel,
+}
+
+/// Keyboard and gamepad navigation of a vertical list of `count` items, without any drawing.
+///
+/// Up/Down or W/S move the selection, holding them repeats after `repeat_delay` every
+/// `repeat_interval` seconds. Enter or Space confirm, Escape or Backspace cancel.
+/// Gamepad d-pad or stick and buttons are passed to [MenuNav::update_with].
+#[derive(Debug, Clone)]
+pub struct MenuNav {
+    pub count: usize,
+    pub selected: usize,
+    /// Moving past the last item selects the first one and the other way around.
+    pub wrap: bool,
+    pub repeat_delay: f32,
+    pub repeat_interval: f32,
+    /// Stick values below this are ignored.
+    pub dead_zone: f32,
+    held_direction: i32,
+    held_time: f32,
+}
+
+impl MenuNav {
+    pub fn new(count: usize) -> MenuNav {
+        MenuNav {
+            count,
+            selected: 0,
+            wrap: true,
+            repeat_delay: 0.4,
+            repeat_interval: 0.1,
+            dead_zone: 0.5,
+            held_direction: 0,
+            held_time: 0.,
+        }
+    }
+
+    /// Update from the keyboard only, once per frame.
+    pub fn update(&mut self) -> Option<MenuAction> {
+        self.update_with(0., false, false)
+    }
+
+    /// Update from the keyboard and a gamepad, once per frame.
+    /// `axis` is the vertical d-pad or stick value, negative is up.
+    pub fn update_with(&mut self, axis: f32, confirm: bool, cancel: bool) -> Option<MenuAction> {
+        use crate::input::{is_key_down, is_key_pressed, KeyCode};
+
+        if confirm || is_key_pressed(KeyCode::Enter) || is_key_pressed(KeyCode::Space) {
+            return (self.count > 0).then_some(MenuAction::Confirm(self.selected));
+        }
+        if cancel || is_key_pressed(KeyCode::Escape) || is_key_pressed(KeyCode::Backspace) {
+            return Some(MenuAction::Cancel);
+        }
+
+        let up = is_key_down(KeyCode::Up) || is_key_down(KeyCode::W) || axis < -self.dead_zone;
+        let down = is_key_down(KeyCode::Down) || is_key_down(KeyCode::S) || axis > self.dead_zone;
+        // real frame time, so menus keep working with a time scale of 0
+        let frame_time = get_context().frame_time as f32;
+
+        let moved = self.step(down as i32 - up as i32, frame_time);
+        moved.then_some(MenuAction::Moved(self.selected))
+    }
+
+    /// Moves the selection for `direction` held during `frame_time`, returns whether it moved.
+    fn step(&mut self, direction: i32, frame_time: f32) -> bool {
+        if direction == 0 || self.count == 0 {
+            self.held_direction = 0;
+            return false;
+        }
+
+        let repeats = if direction != self.held_direction {
+            self.held_direction = direction;
+            self.held_time = 0.;
+            1
+        } else {
+            let before = self.held_time;
+            self.held_time += frame_time;
+            repeats_between(before, self.held_time, self.repeat_delay, self.repeat_interval)
+        };
+
+        let previous = self.selected;
+        for _ in 0..repeats {
+            self.selected = match (direction > 0, self.wrap) {
+                (true, true) => (self.selected + 1) % self.count,
+                (true, false) => (self.selected + 1).min(self.count - 1),
+                (false, true) => (self.selected + self.count - 1) % self.count,
+                (false, false) => self.selected.saturating_sub(1),
+            };
+        }
+        self.selected != previous
+    }
+}
+
+/// Number of repeats of a key held since `delay`, repeating every `interval`, in the `(from, to]` time range.
+fn repeats_between(from: f32, to: f32, delay: f32, interval: f32) -> u32 {
+    let count = |time: f32| if time < delay { 0 } else { ((time - delay) / interval) as u32 + 1 };
+
+    count(to) - count(from)
+}
+
+#[test]
+fn menu_nav_repeat() {
+    let mut nav = MenuNav::new(3);
+
+    // first press moves right away, then waits for the repeat delay
+    assert!(nav.step(1, 0.016));
+    assert_eq!(nav.selected, 1);
+    assert!(!nav.step(1, 0.3));
+    assert!(nav.step(1, 0.11));
+    assert_eq!(nav.selected, 2);
+    assert!(nav.step(1, 0.1));
+    assert_eq!(nav.selected, 0);
+
+    // releasing resets the repeat, up from the first item wraps
+    assert!(!nav.step(0, 0.016));
+    assert!(nav.step(-1, 0.016));
+    assert_eq!(nav.selected, 2);
+
+    nav.wrap = false;
+    assert!(!nav.step(0, 0.016));
+    assert!(!nav.step(1, 0.016));
+    assert_eq!(nav.selected, 2);
+}
@@ -3,6 +3,7 @@
 use crate::{get_context, get_quad_context};
 
 use crate::color::Color;
+use crate::math::{vec2, Mat4, Rect, Vec2};
 
 // miniquad is re-exported for the use in combination with `get_internal_gl`
 pub use miniquad;
@@ -16,6 +17,48 @@ pub fn next_frame() -> crate::exec::FrameFuture {
     crate::exec::FrameFuture::default()
 }
 
+/// Same as `next_frame`, but resolves to the duration of the frame just completed,
+/// the value `get_frame_time()` reports for it.
+/// ```ignore
+/// loop {
+///     let dt = next_frame_timed().await;
+/// }
+/// ```
+#[must_use = "use `next_frame_timed().await` to advance to the next frame"]
+pub fn next_frame_timed() -> crate::exec::TimedFrameFuture {
+    crate::thread_assert::same_thread();
+    crate::exec::TimedFrameFuture::default()
+}
+
+/// Finish frames until `condition` returns true.
+///
+/// The condition is first checked on the next frame, after the current one is drawn.
+/// ```ignore
+/// wait_until(|| get_time() > 3.0).await;
+/// ```
+pub async fn wait_until(mut condition: impl FnMut() -> bool) {
+    loop {
+        next_frame().await;
+        if condition() {
+            return;
+        }
+    }
+}
+
+/// Finish frames until `key_code` gets pressed, e.g. for "press space to continue" screens.
+/// Presses on the current frame are ignored.
+pub async fn wait_for_key(key_code: crate::input::KeyCode) {
+    wait_until(|| crate::input::is_key_pressed(key_code)).await
+}
+
+/// Finish frames until the left mouse button gets pressed.
+/// Clicks on the current frame are ignored.
+pub async fn wait_for_click() {
+    use crate::input::{read_frame_input, MouseButton};
+
+    wait_until(|| read_frame_input().mouse_pressed.contains(&MouseButton::Left)).await
+}
+
 /// Fill window background with solid color.
 /// Note: even when "clear_background" is not called explicitly,
 /// the screen will be cleared at the beginning of the frame.
@@ -44,6 +87,30 @@ impl<'a> InternalGlContext<'a> {
     }
 }
 
+/// Submit all the draw calls batched so far to miniquad.
+///
+/// Macroquad batches drawing and only submits it when the camera or render target changes
+/// and at the end of the frame. After `flush` everything drawn before it has been issued
+/// to the rendering backend, so raw miniquad calls made afterwards are ordered after it.
+pub fn flush() {
+    get_context().perform_render_passes();
+}
+
+/// `flush`, then with OpenGL also `glFlush`, so all macroquad drawing queued so far
+/// is submitted to the GPU before the next raw GL call, e.g. when mixing
+/// macroquad with compute or raw GL rendering sharing the same textures.
+///
+/// Does not wait for the GPU to finish the work.
+pub fn gl_barrier() {
+    flush();
+
+    if get_quad_context().info().backend == miniquad::Backend::OpenGl {
+        unsafe {
+            miniquad::gl::glFlush();
+        }
+    }
+}
+
 pub unsafe fn get_internal_gl<'a>() -> InternalGlContext<'a> {
     let context = get_context();
 
@@ -53,21 +120,76 @@ pub unsafe fn get_internal_gl<'a>() -> InternalGlContext<'a> {
     }
 }
 
+/// Screen width in logical pixels, the unit of drawing with the default camera and of mouse positions.
 pub fn screen_width() -> f32 {
     let context = get_context();
     context.screen_width / miniquad::window::dpi_scale()
 }
 
+/// Screen height in logical pixels, the unit of drawing with the default camera and of mouse positions.
 pub fn screen_height() -> f32 {
     let context = get_context();
 
     context.screen_height / miniquad::window::dpi_scale()
 }
 
+/// Ratio of physical pixels to logical pixels.
 pub fn screen_dpi_scale() -> f32 {
     miniquad::window::dpi_scale()
 }
 
+/// Returns true on the frame the dpi scale changed, e.g. after the window moved to another monitor.
+///
+/// A good time to switch fonts to atlases baked for the new scale with [crate::text::Font::rebuild_for_dpi].
+pub fn screen_dpi_changed() -> bool {
+    get_context().dpi_changed
+}
+
+/// Screen width in physical pixels, e.g. the size of `get_screen_data` or of a full resolution render target.
+pub fn screen_width_physical() -> u32 {
+    get_context().screen_width as u32
+}
+
+/// Screen height in physical pixels, e.g. the size of `get_screen_data` or of a full resolution render target.
+pub fn screen_height_physical() -> u32 {
+    get_context().screen_height as u32
+}
+
+/// Size of the default framebuffer in physical pixels, see `screen_width_physical`.
+pub fn framebuffer_size() -> (u32, u32) {
+    (screen_width_physical(), screen_height_physical())
+}
+
+/// Area visible through the active camera, in its world units.
+///
+/// Without a camera it is the same as [screen_pixel_rect]. For a rotated camera this is the
+/// bounding box of the visible area.
+pub fn screen_rect() -> Rect {
+    let inverse = get_context().projection_matrix().inverse();
+
+    let corners = [vec2(-1., -1.), vec2(1., -1.), vec2(1., 1.), vec2(-1., 1.)];
+    let corners = corners.map(|corner| inverse.transform_point3(corner.extend(0.)).truncate());
+    let min = corners.iter().fold(corners[0], |min, corner| min.min(*corner));
+    let max = corners.iter().fold(corners[0], |max, corner| max.max(*corner));
+
+    Rect::new(min.x, min.y, max.x - min.x, max.y - min.y)
+}
+
+/// The whole window in logical pixels, regardless of the active camera.
+pub fn screen_pixel_rect() -> Rect {
+    Rect::new(0., 0., screen_width(), screen_height())
+}
+
+/// Moves `pos`, the top left corner of an object of `size`, so the object stays within [screen_rect].
+///
+/// Objects bigger than the screen are aligned to its top left corner.
+pub fn clamp_to_screen(pos: Vec2, size: Vec2) -> Vec2 {
+    let screen = screen_rect();
+    let max = (screen.point() + screen.size() - size).max(screen.point());
+
+    pos.clamp(screen.point(), max)
+}
+
 /// Request the window size to be the given value. This takes DPI into account.
 ///
 /// Note that the OS might decide to give a different size. Additionally, the size in macroquad won't be updated until the next `next_frame().await`.
@@ -81,7 +203,340 @@ pub fn request_new_screen_size(width: f32, height: f32) {
     // Because the OS might decide to give a different screen dimension, setting the context.screen_* here would be confusing.
 }
 
+/// Corner of the screen used as (0, 0) when no camera is set.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Default)]
+pub enum Origin {
+    /// y goes down, the default.
+    #[default]
+    TopLeft,
+    /// y goes up, as in OpenGL.
+    BottomLeft,
+}
+
+/// Set the origin of the pixel coordinates used when no camera is set.
+///
+/// Only affects drawing, mouse and touch positions are still relative to the top left corner.
+pub fn set_pixel_projection_origin(origin: Origin) {
+    get_context().pixel_origin = origin;
+}
+
+/// Returns the projection matrix used when no camera is set,
+/// mapping logical pixels to normalized device coordinates.
+pub fn pixel_projection_matrix() -> Mat4 {
+    get_context().pixel_perfect_projection_matrix()
+}
+
+/// Render the whole frame at `scale` times the window resolution, then stretch it over the window.
+///
+/// Values below 1 trade sharpness for fill rate, values above 1 supersample.
+/// Drawing with the default camera or any camera without a render target goes to the scaled frame,
+/// so coordinates, cameras and mouse positions stay in logical window pixels and need no remapping.
+/// Viewports and scissor rects are in pixels of the scaled frame. Takes effect from the next frame.
+pub fn set_render_scale(scale: f32) {
+    assert!(scale > 0., "render scale should be positive");
+
+    get_context().render_scale = scale;
+}
+
+/// Returns the scale set by [set_render_scale], 1 by default.
+pub fn render_scale() -> f32 {
+    get_context().render_scale
+}
+
 /// Toggle whether the window is fullscreen.
 pub fn set_fullscreen(fullscreen: bool) {
+    get_context().fullscreen = fullscreen;
     miniquad::window::set_fullscreen(fullscreen);
 }
+
+/// Returns the text on the OS clipboard, `None` if it is empty, not text or the platform has no clipboard.
+///
+/// On the web the browser only hands out the clipboard on paste, so this returns the text of
+/// the last Ctrl+V in the page, or `None` before the first one.
+/// ```ignore
+/// if is_key_down(KeyCode::LeftControl) && is_key_pressed(KeyCode::C) {
+///     set_clipboard(&format!("{} fps", get_fps()));
+/// }
+/// ```
+pub fn get_clipboard() -> Option<String> {
+    miniquad::window::clipboard_get()
+}
+
+/// Put `text` on the OS clipboard, does nothing on platforms without a clipboard.
+///
+/// On the web the text only reaches the clipboard on the next copy or cut of the browser,
+/// so set it before or while Ctrl+C is pressed, like in the [get_clipboard] example.
+pub fn set_clipboard(text: &str) {
+    miniquad::window::clipboard_set(text);
+}
+
+/// Window and graphics settings currently in effect, see [current_conf].
+///
+/// Field names follow `Conf`, so a saved snapshot can be fed back into it on the next start.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ConfSnapshot {
+    /// Window width in logical pixels.
+    pub window_width: i32,
+    /// Window height in logical pixels.
+    pub window_height: i32,
+    pub fullscreen: bool,
+    /// `false` only when the swap interval was explicitly set to 0.
+    pub vsync: bool,
+    /// MSAA sample count of the default framebuffer, 1 means no multisampling.
+    pub sample_count: i32,
+}
+
+/// Returns the settings currently applied to the window, e.g. to show and save them in a settings menu.
+///
+/// Reflects the startup `Conf` and later calls to `set_fullscreen` or window resizes.
+pub fn current_conf() -> ConfSnapshot {
+    let context = get_context();
+
+    ConfSnapshot {
+        window_width: screen_width() as i32,
+        window_height: screen_height() as i32,
+        fullscreen: context.fullscreen,
+        vsync: context.swap_interval != Some(0),
+        sample_count: context.sample_count,
+    }
+}
+
+/// Graphics API used for rendering, see [graphics_info].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GraphicsBackend {
+    OpenGl,
+    /// OpenGL ES, including WebGL.
+    OpenGlEs,
+    Metal,
+}
+
+/// Rendering backend and driver details, see [graphics_info].
+#[derive(Debug, Clone)]
+pub struct GraphicsInfo {
+    pub backend: GraphicsBackend,
+    /// GPU vendor, as reported by the driver, empty on Metal.
+    pub vendor: String,
+    /// GPU and driver name, e.g. "Mesa Intel(R) UHD Graphics 620", empty on Metal.
+    pub renderer: String,
+    /// Full `GL_VERSION` string, e.g. "4.6 (Core Profile) Mesa 23.2.1", empty on Metal.
+    pub version: String,
+    /// Whether instanced drawing is available, false on WebGL1 and GL2 without the extension.
+    pub instancing: bool,
+    /// Always false for now, miniquad has no compute shaders on any backend.
+    pub compute_shaders: bool,
+}
+
+/// Returns which graphics API and driver render the game, e.g. to log them with bug reports
+/// or to turn features off on old drivers.
+pub fn graphics_info() -> GraphicsInfo {
+    // not in miniquad's gl bindings
+    const GL_RENDERER: u32 = 0x1F01;
+
+    let info = get_quad_context().info();
+    let gl_string = |name| unsafe {
+        let string = miniquad::gl::glGetString(name);
+        if string.is_null() {
+            String::new()
+        } else {
+            std::ffi::CStr::from_ptr(string as _).to_string_lossy().into_owned()
+        }
+    };
+
+    let (vendor, renderer) = match info.backend {
+        miniquad::Backend::OpenGl => (gl_string(miniquad::gl::GL_VENDOR), gl_string(GL_RENDERER)),
+        miniquad::Backend::Metal => (String::new(), String::new()),
+    };
+    let backend = match info.backend {
+        miniquad::Backend::Metal => GraphicsBackend::Metal,
+        _ if cfg!(target_arch = "wasm32") || info.gl_version_string.contains("OpenGL ES") => GraphicsBackend::OpenGlEs,
+        miniquad::Backend::OpenGl => GraphicsBackend::OpenGl,
+    };
+
+    GraphicsInfo {
+        backend,
+        vendor,
+        renderer,
+        version: info.gl_version_string,
+        instancing: info.features.instancing,
+        compute_shaders: false,
+    }
+}
+
+/// Quit when Escape is pressed, off by default.
+///
+/// Goes through `prevent_quit` and `on_quit_requested` like closing the window does.
+pub fn set_exit_on_escape(enabled: bool) {
+    get_context().exit_on_escape = enabled;
+}
+
+/// Call `handler` with the new screen size in logical pixels, as `screen_width` and `screen_height`
+/// report them, whenever the window is resized, e.g. to lay out UI or recreate render targets.
+///
+/// The handler runs from the resize event, outside of the main loop, so it sees every size
+/// change even with a blocking event loop. Replaces the previous handler.
+pub fn on_resize(handler: impl FnMut(f32, f32) + 'static) {
+    get_context().resize_handler = Some(Box::new(handler));
+}
+
+/// Wait for the GPU to finish the frame (`glFlush` + `glFinish`) at the end of each frame
+/// on web and Linux, on by default.
+///
+/// The wait keeps frame pacing and input latency steady with drivers that otherwise queue
+/// several frames ahead, at the cost of stalling the CPU until the GPU is done.
+/// Turning it off may raise the frame rate of GPU bound games, but frames may then be presented
+/// later than they were drawn and `get_frame_time` measures CPU time only.
+pub fn set_frame_sync(enabled: bool) {
+    get_context().frame_sync = enabled;
+}
+
+pub fn frame_sync() -> bool {
+    get_context().frame_sync
+}
+
+/// Returns whether the window is currently minimized (or, on mobile, the app is in the background).
+///
+/// Useful to skip rendering or pause audio while nothing is visible.
+/// See also `is_window_focused`.
+pub fn is_window_minimized() -> bool {
+    get_context().window_minimized
+}
+
+/// Make dragging with the left mouse button inside `region` (in logical pixels) move the window,
+/// for borderless windows with a custom title bar. `None` disables it.
+///
+/// Only supported on Windows and Linux, a no-op elsewhere.
+/// Resizing from the window edges is not handled.
+pub fn set_window_drag_region(region: Option<Rect>) {
+    let context = get_context();
+
+    context.window_drag_region = region;
+    if region.is_none() {
+        context.window_drag_start = None;
+    }
+}
+
+/// Start dragging the window if the mouse button went down inside the drag region.
+/// `x` and `y` are in physical pixels.
+pub(crate) fn begin_window_drag(x: f32, y: f32) {
+    let context = get_context();
+    let dpi = miniquad::window::dpi_scale();
+
+    if let Some(region) = context.window_drag_region {
+        if region.contains(vec2(x / dpi, y / dpi)) {
+            context.window_drag_start = Some(vec2(x, y));
+        }
+    }
+}
+
+/// Move the window so the point grabbed in `begin_window_drag` stays under the mouse.
+#[cfg_attr(not(any(target_os = "windows", target_os = "linux")), allow(unused_variables))]
+pub(crate) fn update_window_drag(x: f32, y: f32) {
+    #[cfg(any(target_os = "windows", target_os = "linux"))]
+    if let Some(start) = get_context().window_drag_start {
+        let (window_x, window_y) = miniquad::window::get_window_position();
+        miniquad::window::set_window_position(
+            (window_x as f32 + x - start.x).max(0.) as u32,
+            (window_y as f32 + y - start.y).max(0.) as u32,
+        );
+    }
+}
+
+pub(crate) fn end_window_drag() {
+    get_context().window_drag_start = None;
+}
+
+/// A screen of the game, e.g. the menu, the level being played or the pause menu, driven by a [StateStack].
+pub trait GameState {
+    /// Called once per frame while this state is on top of the stack.
+    fn update(&mut self) -> StateTransition;
+
+    /// Called once per frame while this state is visible.
+    fn draw(&mut self);
+
+    /// Whether the states below stay visible, drawn first, e.g. a pause menu over the frozen level.
+    /// Only the top state is updated either way.
+    fn is_overlay(&self) -> bool {
+        false
+    }
+}
+
+/// What happens to the [StateStack] after the top state's update.
+pub enum StateTransition {
+    /// Keep the top state.
+    None,
+    /// Put a state on top, e.g. open the pause menu.
+    Push(Box<dyn GameState>),
+    /// Remove the top state, e.g. close the pause menu.
+    Pop,
+    /// Swap the top state for another one, e.g. from the menu to the level.
+    Replace(Box<dyn GameState>),
+}
+
+/// Stack of [GameState]s, the top one being updated, instead of a match on the current screen in the main loop.
+/// ```ignore
+/// StateStack::new(Menu::default()).run().await;
+/// ```
+pub struct StateStack {
+    states: Vec<Box<dyn GameState>>,
+}
+
+impl StateStack {
+    pub fn new(initial: impl GameState + 'static) -> StateStack {
+        StateStack {
+            states: vec![Box::new(initial)],
+        }
+    }
+
+    pub fn push(&mut self, state: impl GameState + 'static) {
+        self.states.push(Box::new(state));
+    }
+
+    pub fn pop(&mut self) -> Option<Box<dyn GameState>> {
+        self.states.pop()
+    }
+
+    pub fn len(&self) -> usize {
+        self.states.len()
+    }
+
+    /// True once the last state popped itself.
+    pub fn is_empty(&self) -> bool {
+        self.states.is_empty()
+    }
+
+    /// Update the top state and apply the transition it returned.
+    pub fn update(&mut self) {
+        let Some(top) = self.states.last_mut() else {
+            return;
+        };
+
+        match top.update() {
+            StateTransition::None => {}
+            StateTransition::Push(state) => self.states.push(state),
+            StateTransition::Pop => {
+                self.states.pop();
+            }
+            StateTransition::Replace(state) => {
+                self.states.pop();
+                self.states.push(state);
+            }
+        }
+    }
+
+    /// Draw the visible states, from the bottom one to the top one.
+    pub fn draw(&mut self) {
+        let first_visible = self.states.iter().rposition(|state| !state.is_overlay()).unwrap_or(0);
+        for state in &mut self.states[first_visible..] {
+            state.draw();
+        }
+    }
+
+    /// Update and draw the states every frame until the stack is empty.
+    pub async fn run(mut self) {
+        while !self.is_empty() {
+            self.update();
+            self.draw();
+            next_frame().await;
+        }
+    }
+}